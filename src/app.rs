@@ -1,24 +1,83 @@
 use crate::filesystem::{
-    copy_entry, create_directory, create_file, create_zip, decrypt_file, delete_entry,
-    encrypt_file, extract_zip, get_drives, read_directory, rename_entry,
-    search_directory_recursive, FileEntry, FileType,
+    build_content_matcher, collect_paths, copy_entry, create_directory, create_file, create_zip,
+    decrypt_file, delete_entry, dir_mtime, encrypt_file, extract_zip, file_category,
+    file_entry_from_metadata, folder_size, get_drives, rename_entry, search_directory_recursive,
+    search_file_contents, stream_directory, trash_entry, ContentMatch, DriveInfo, FileCategory, FileEntry, FileType,
+    FolderSize, ListingEvent, SearchFilter, WalkOptions,
 };
+use crate::cache::ListingCache;
+use crate::document::{LineEnding, TextDocument, ENCODING_CHOICES};
+use crate::hex::{self, HexFile, BYTES_PER_ROW};
+use crate::highlight::Highlighter;
+use crate::imageinfo::{ImageMeta, Orientation};
+use crate::index::{spawn_indexer, FileIndex};
+use crate::markdown::{self, MarkdownView};
+use crate::mime::{self, ContentKind, TEXT_EDITOR};
+use crate::pager::PagedFile;
+use crate::pdf::{spawn_renderer, PdfPreview, PdfRequest, PdfUpdate};
+use crate::table::{self, CsvTable};
+use crate::usage::{scan_usage, treemap, UsageNode};
 use eframe::egui;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
 use humansize::{format_size, DECIMAL};
-use chrono::{DateTime, Local, TimeZone};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use chrono::{DateTime, Local, NaiveDate, TimeZone};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::Arc;
 use std::thread;
+use std::time::{Instant, SystemTime};
 
 const CONFIG_FILENAME: &str = "rust_explorer_config.json";
+const INDEX_RESULT_LIMIT: usize = 500;
+const QUICK_OPEN_SCAN_LIMIT: usize = 50_000;
+const QUICK_OPEN_RESULTS: usize = 20;
+const RECENT_LIMIT: usize = 50;
+const USAGE_LARGEST_FILES: usize = 20;
+// What the image loaders installed in `new` can decode
+const PREVIEW_IMAGE_TYPES: &[&str] = &[
+    "image/png",
+    "image/jpeg",
+    "image/gif",
+    "image/bmp",
+    "image/webp",
+    "image/svg+xml",
+    "image/x-icon",
+    "image/vnd.microsoft.icon",
+    "image/tiff",
+    "image/vnd.ms-dds",
+    "image/x-farbfeld",
+    "image/x-portable-anymap",
+    "image/x-tga",
+];
+// Text files up to this size open in the editor, bigger ones in the paged viewer
+const FULL_TEXT_LIMIT: u64 = 256 * 1024;
+const PAGER_INDEX_BUDGET: usize = 32 * 1024 * 1024; // Bytes scanned for lines per frame
+const TABLE_LOAD_BUDGET: usize = 20_000; // CSV records read per frame
+const FOLLOW_INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
+const DRIVE_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(3);
 
 #[derive(PartialEq, Serialize, Deserialize, Clone, Copy)]
 enum ViewMode {
     List,
     Grid,
+    Usage, // Disk usage treemap of current_path
+}
+
+// A recursive search kept in the side panel and opened like a folder
+#[derive(Clone, Serialize, Deserialize)]
+struct SavedSearch {
+    name: String,
+    root: PathBuf,
+    query: String,
+    #[serde(default)]
+    filter: SearchFilter,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -30,6 +89,16 @@ struct AppConfig {
     sort_order: SortOrder,
     last_path: PathBuf,
     view_mode: ViewMode,
+    #[serde(default)]
+    walk_options: WalkOptions,
+    #[serde(default)]
+    index_enabled: bool,
+    #[serde(default)]
+    recent_paths: Vec<PathBuf>,
+    #[serde(default)]
+    saved_searches: Vec<SavedSearch>,
+    #[serde(default)]
+    auto_folder_sizes: bool,
 }
 
 impl Default for AppConfig {
@@ -48,6 +117,11 @@ impl Default for AppConfig {
             sort_order: SortOrder::Ascending,
             last_path: std::env::current_dir().unwrap_or(PathBuf::from("C:\\")),
             view_mode: ViewMode::List,
+            walk_options: WalkOptions::default(),
+            index_enabled: false,
+            recent_paths: Vec::new(),
+            saved_searches: Vec::new(),
+            auto_folder_sizes: false,
         }
     }
 }
@@ -70,9 +144,12 @@ impl AppConfig {
 }
 
 enum PreviewData {
-    Text(String),
-    Image(PathBuf),
-    Pdf(PathBuf),
+    Text(TextDocument),
+    Paged(PagedFile), // Text too large to load, viewed read-only
+    Table(CsvTable),
+    Hex(HexFile), // Anything else, as raw bytes
+    Image(PathBuf, ImageMeta),
+    Pdf(PdfPreview),
 }
 
 #[derive(PartialEq, Serialize, Deserialize)]
@@ -95,6 +172,46 @@ enum Theme {
     Mocha,
 }
 
+impl Theme {
+    // Bundled syntect theme closest to each app theme
+    fn syntax_theme(self) -> &'static str {
+        match self {
+            Theme::Dark => "base16-ocean.dark",
+            Theme::Light => "InspiredGitHub",
+            Theme::Mocha => "base16-mocha.dark",
+        }
+    }
+}
+
+struct LoadRequest {
+    generation: u64,
+    path: PathBuf,
+    cancel: Arc<AtomicBool>, // Set once a newer navigation supersedes this one
+    cached_mtime: Option<SystemTime>, // A cached listing is already shown, skip the read if still current
+}
+
+// Directory listing progress, tagged with the request it answers
+struct LoadUpdate {
+    generation: u64,
+    path: PathBuf,
+    event: LoadEvent,
+}
+
+enum LoadEvent {
+    Listing(ListingEvent),
+    Unchanged,                // The cached listing on screen is current
+    Done(Option<SystemTime>), // Folder mtime as of the read, for the cache
+    Failed(String),
+}
+
+// Streamed results from a background search, tagged with the search id
+enum SearchUpdate {
+    Entries(u64, Vec<FileEntry>),
+    Matches(u64, Vec<ContentMatch>),
+    Failed(u64, String),
+    Done(u64),
+}
+
 #[derive(PartialEq, Clone, Copy)]
 enum PasswordAction {
     Encrypt,
@@ -106,11 +223,35 @@ pub struct ExplorerApp {
     history: Vec<PathBuf>,
     forward_stack: Vec<PathBuf>,
     entries: Vec<FileEntry>,
-    drives: Vec<PathBuf>,
+    drives: Vec<DriveInfo>,
+    drives_rx: Receiver<Vec<DriveInfo>>,
 
     // Selection & State
     selected_entry: Option<usize>,
     preview_data: Option<PreviewData>,
+    preview_path: Option<PathBuf>,
+    preview_mime: Option<&'static str>,
+    preview_scroll_line: Option<usize>, // One-shot scroll request for the text preview
+    pdf_tx: Sender<PdfRequest>,
+    pdf_rx: Receiver<PdfUpdate>,
+    highlighter: Highlighter,
+    line_gutter: (usize, String), // Editor line numbers, rebuilt when the line count changes
+    markdown: MarkdownView,
+    markdown_rendered: bool, // Markdown previews show the rendered document, not the source
+    table_view: bool,        // CSV/TSV previews show the grid, not the editable source
+    preview_goto: String, // Jump-to-line input of the paged viewer
+    preview_follow: bool, // Paged viewer tails the file
+    follow_checked: Instant, // When the followed file was last checked for growth
+    hex_cursor: usize,    // Byte offset selected in the hex viewer
+    hex_find: String,     // Byte pattern search input of the hex viewer
+    hex_status: Option<String>, // Outcome of the last hex search, if it failed
+    hex_search: Option<Arc<AtomicBool>>, // Find running in the background, set to cancel it
+    hex_search_id: u64,                  // Results of older finds are dropped
+    hex_search_tx: Sender<(u64, Result<Option<usize>, String>)>,
+    hex_search_rx: Receiver<(u64, Result<Option<usize>, String>)>,
+    hex_jump: bool, // Scroll the hex cursor into view on the next frame
+    pending_preview: Option<Option<PathBuf>>, // Preview to show once unsaved edits are dealt with
+    save_conflict: bool, // Save stopped because the file changed on disk
     error_message: Option<String>,
     show_hidden: bool,
     theme: Theme,
@@ -123,10 +264,46 @@ pub struct ExplorerApp {
     rename_buffer: String,
 
     // Threading
-    load_req_tx: Sender<PathBuf>,
-    load_res_rx: Receiver<Result<Vec<FileEntry>, String>>,
-    load_res_tx: Sender<Result<Vec<FileEntry>, String>>, // Kept for ad-hoc tasks
-    is_loading: bool,
+    load_req_tx: Sender<LoadRequest>,
+    load_res_rx: Receiver<LoadUpdate>,
+    load_generation: u64,
+    load_cancel: Arc<AtomicBool>,
+    is_loading: bool, // No part of the listing has arrived yet
+    revalidating: bool, // Showing a cached listing until the loader confirms or replaces it
+    listing_incomplete: bool, // Names shown, metadata still streaming in
+    load_positions: HashMap<PathBuf, usize>, // Where each listed path sits, for metadata updates
+    listing_hidden: HashMap<PathBuf, FileEntry>, // Hidden entries kept out of view while listing
+    listing_cache: ListingCache,
+    showing_search_results: bool, // `entries` holds search hits rather than the listing
+
+    // Recursive folder sizes, computed on request or for every listed folder
+    auto_folder_sizes: bool,
+    folder_sizes: HashMap<PathBuf, FolderSize>,
+    sniffed: HashMap<PathBuf, FileCategory>, // Categories read from content, for files shown so far
+    folder_sizes_pending: HashSet<PathBuf>,
+    size_req_tx: Sender<(PathBuf, Arc<AtomicBool>)>,
+    size_res_rx: Receiver<(PathBuf, FolderSize)>,
+
+    // Disk usage view
+    usage: Option<UsageNode>,
+    usage_scanning: Option<PathBuf>, // Root of the scan in progress
+    usage_id: u64,
+    usage_cancel: Option<Arc<AtomicBool>>,
+    usage_tx: Sender<(u64, UsageNode)>,
+    usage_rx: Receiver<(u64, UsageNode)>,
+    usage_confirm: Option<(PathBuf, bool)>, // Removal awaiting confirmation, true = to trash
+
+    // Live refresh of current_path
+    watcher: Option<RecommendedWatcher>,
+    watched_path: Option<PathBuf>,
+    watch_rx: Receiver<notify::Result<notify::Event>>,
+    pending_fs_changes: HashSet<PathBuf>,
+    search_res_tx: Sender<SearchUpdate>,
+    search_res_rx: Receiver<SearchUpdate>,
+    search_id: u64,
+    search_cancel: Option<Arc<AtomicBool>>,
+    is_searching: bool,
+    search_ranked: bool, // Results are in relevance order, don't re-sort on completion
     path_input: String,
 
     // New Features
@@ -142,6 +319,44 @@ pub struct ExplorerApp {
 
     // Feature State
     recursive_search: bool,
+    content_search: bool,
+    content_regex: bool,
+    search_filter: SearchFilter,
+    walk_options: WalkOptions,
+    excludes_buffer: String, // One exclusion glob per line, mirrors walk_options.excludes
+    filter_window_open: bool,
+
+    // Filename index (opt-in, covers the favorites)
+    index_enabled: bool,
+    index: Option<Arc<FileIndex>>,
+    index_tx: Sender<(u64, Arc<FileIndex>)>,
+    index_rx: Receiver<(u64, Arc<FileIndex>)>,
+    index_stop: Option<Arc<AtomicBool>>,
+    index_generation: u64, // Snapshots from an indexer that was since restarted are dropped
+
+    // Quick Open (Ctrl+P)
+    quick_open: bool,
+    quick_open_focus: bool,
+    quick_open_query: String,
+    quick_open_candidates: Vec<(PathBuf, bool)>, // (path, is_dir) under current_path
+    quick_open_results: Vec<(PathBuf, bool)>,
+    quick_open_scanning: bool,
+    quick_open_selected: usize,
+    quick_open_id: u64,
+    quick_open_tx: Sender<(u64, Vec<(PathBuf, bool)>)>,
+    quick_open_rx: Receiver<(u64, Vec<(PathBuf, bool)>)>,
+    recent_paths: Vec<PathBuf>, // Most recent first
+
+    // Saved searches
+    saved_searches: Vec<SavedSearch>,
+    active_saved_search: Option<usize>, // Viewing this search as a virtual folder
+    save_search_popup_open: bool,
+    save_search_name: String,
+    pending_select: Option<PathBuf>, // Selected once the current listing arrives
+    grid_scroll: (f32, f32), // Grid view scroll offset and viewport height from the last frame
+
+    content_results: Option<Vec<ContentMatch>>, // Some while showing grep results
+    selected_match: Option<usize>,
     image_zoom: f32,
     image_offset: egui::Vec2,
     focus_search: bool,
@@ -154,17 +369,100 @@ pub struct ExplorerApp {
 impl ExplorerApp {
     pub fn new(_cc: &eframe::CreationContext<'_>) -> Self {
         egui_extras::install_image_loaders(&_cc.egui_ctx);
-        let (tx, rx) = channel::<PathBuf>();
+        let (tx, rx) = channel::<LoadRequest>();
         let (res_tx, res_rx) = channel();
-        let res_tx_clone = res_tx.clone();
+        let (search_res_tx, search_res_rx) = channel();
+        let (hex_search_tx, hex_search_rx) = channel();
+        let (index_tx, index_rx) = channel();
+        let (quick_open_tx, quick_open_rx) = channel();
+        let (watch_tx, watch_rx) = channel();
+        let (size_req_tx, size_req_rx) = channel::<(PathBuf, Arc<AtomicBool>)>();
+        let (size_res_tx, size_res_rx) = channel();
+        let (usage_tx, usage_rx) = channel();
+        let (drives_tx, drives_rx) = channel();
+        let (pdf_tx, pdf_req_rx) = channel();
+        let (pdf_res_tx, pdf_rx) = channel();
+
+        let repaint_ctx = _cc.egui_ctx.clone();
+        let watcher = notify::recommended_watcher(move |event| {
+            let _ = watch_tx.send(event);
+            repaint_ctx.request_repaint();
+        })
+        .map_err(|e| log::warn!("File watcher unavailable: {}", e))
+        .ok();
+
+        let load_ctx = _cc.egui_ctx.clone();
+        thread::spawn(move || {
+            while let Ok(mut request) = rx.recv() {
+                // Skip straight to the newest request if the user navigated on
+                while let Ok(newer) = rx.try_recv() {
+                    request = newer;
+                }
+                let LoadRequest {
+                    generation,
+                    path,
+                    cancel,
+                    cached_mtime,
+                } = request;
+                let send = |event| {
+                    let _ = res_tx.send(LoadUpdate {
+                        generation,
+                        path: path.clone(),
+                        event,
+                    });
+                    load_ctx.request_repaint();
+                };
+
+                // Taken before reading so changes made during the read still count as newer
+                let mtime = dir_mtime(&path);
+                if mtime.is_some() && mtime == cached_mtime {
+                    send(LoadEvent::Unchanged);
+                    continue;
+                }
+
+                let result = stream_directory(&path, &cancel, |event| {
+                    send(LoadEvent::Listing(event));
+                });
+                if cancel.load(Ordering::Relaxed) {
+                    continue;
+                }
+                send(match result {
+                    Ok(()) => LoadEvent::Done(mtime),
+                    Err(e) => LoadEvent::Failed(e),
+                });
+            }
+        });
+
+        // Folder sizes share the listing's cancel flag, so leaving a folder drops its queue
+        let size_ctx = _cc.egui_ctx.clone();
+        thread::spawn(move || {
+            while let Ok((path, cancel)) = size_req_rx.recv() {
+                if let Some(size) = folder_size(&path, &cancel) {
+                    let _ = size_res_tx.send((path, size));
+                    size_ctx.request_repaint();
+                }
+            }
+        });
 
+        // sysinfo has no mount notifications, so poll and report when anything changed
+        let drives_ctx = _cc.egui_ctx.clone();
         thread::spawn(move || {
-            while let Ok(path) = rx.recv() {
-                let result = read_directory(&path);
-                let _ = res_tx.send(result);
+            let mut last = get_drives();
+            loop {
+                thread::sleep(DRIVE_POLL_INTERVAL);
+                let drives = get_drives();
+                if drives != last {
+                    if drives_tx.send(drives.clone()).is_err() {
+                        return;
+                    }
+                    drives_ctx.request_repaint();
+                    last = drives;
+                }
             }
         });
 
+        spawn_renderer(pdf_req_rx, pdf_res_tx, _cc.egui_ctx.clone());
+
         let config = AppConfig::load();
         let start_path = if config.last_path.exists() {
             config.last_path.clone()
@@ -172,16 +470,47 @@ impl ExplorerApp {
             std::env::current_dir().unwrap_or(PathBuf::from("C:\\"))
         };
 
-        tx.send(start_path.clone()).unwrap();
+        let load_cancel = Arc::new(AtomicBool::new(false));
+        tx.send(LoadRequest {
+            generation: 0,
+            path: start_path.clone(),
+            cancel: load_cancel.clone(),
+            cached_mtime: None,
+        })
+        .unwrap();
 
-        let app = Self {
+        let mut app = Self {
             current_path: start_path.clone(),
             history: Vec::new(),
             forward_stack: Vec::new(),
             entries: Vec::new(),
             drives: get_drives(),
+            drives_rx,
             selected_entry: None,
             preview_data: None,
+            preview_path: None,
+            preview_mime: None,
+            pdf_tx,
+            pdf_rx,
+            preview_scroll_line: None,
+            highlighter: Highlighter::new(),
+            line_gutter: (0, String::new()),
+            markdown: MarkdownView::new(),
+            markdown_rendered: true,
+            table_view: true,
+            preview_goto: String::new(),
+            preview_follow: false,
+            follow_checked: Instant::now(),
+            hex_cursor: 0,
+            hex_find: String::new(),
+            hex_status: None,
+            hex_search: None,
+            hex_search_id: 0,
+            hex_search_tx,
+            hex_search_rx,
+            hex_jump: false,
+            pending_preview: None,
+            save_conflict: false,
             error_message: None,
             show_hidden: config.show_hidden,
             theme: config.theme,
@@ -190,8 +519,38 @@ impl ExplorerApp {
             rename_buffer: String::new(),
             load_req_tx: tx,
             load_res_rx: res_rx,
-            load_res_tx: res_tx_clone,
+            load_generation: 0,
+            load_cancel,
             is_loading: true,
+            revalidating: false,
+            listing_incomplete: false,
+            load_positions: HashMap::new(),
+            listing_hidden: HashMap::new(),
+            listing_cache: ListingCache::default(),
+            auto_folder_sizes: config.auto_folder_sizes,
+            folder_sizes: HashMap::new(),
+            sniffed: HashMap::new(),
+            folder_sizes_pending: HashSet::new(),
+            size_req_tx,
+            size_res_rx,
+            usage: None,
+            usage_scanning: None,
+            usage_id: 0,
+            usage_cancel: None,
+            usage_tx,
+            usage_rx,
+            usage_confirm: None,
+            showing_search_results: false,
+            watcher,
+            watched_path: None,
+            watch_rx,
+            pending_fs_changes: HashSet::new(),
+            search_res_tx,
+            search_res_rx,
+            search_id: 0,
+            search_cancel: None,
+            is_searching: false,
+            search_ranked: false,
             path_input: start_path.to_string_lossy().to_string(),
             search_query: String::new(),
             sort_column: config.sort_column,
@@ -203,6 +562,37 @@ impl ExplorerApp {
             path_edit_mode: false,
             view_mode: config.view_mode,
             recursive_search: false,
+            content_search: false,
+            content_regex: false,
+            search_filter: SearchFilter::default(),
+            excludes_buffer: config.walk_options.excludes.join("\n"),
+            walk_options: config.walk_options,
+            filter_window_open: false,
+            index_enabled: config.index_enabled,
+            index: None,
+            index_tx,
+            index_rx,
+            index_stop: None,
+            index_generation: 0,
+            quick_open: false,
+            quick_open_focus: false,
+            quick_open_query: String::new(),
+            quick_open_candidates: Vec::new(),
+            quick_open_results: Vec::new(),
+            quick_open_scanning: false,
+            quick_open_selected: 0,
+            quick_open_id: 0,
+            quick_open_tx,
+            quick_open_rx,
+            recent_paths: config.recent_paths,
+            saved_searches: config.saved_searches,
+            active_saved_search: None,
+            save_search_popup_open: false,
+            save_search_name: String::new(),
+            pending_select: None,
+            grid_scroll: (0.0, 0.0),
+            content_results: None,
+            selected_match: None,
             image_zoom: 1.0,
             image_offset: egui::Vec2::ZERO,
             focus_search: false,
@@ -212,9 +602,35 @@ impl ExplorerApp {
         };
 
         app.apply_theme(&_cc.egui_ctx);
+        app.watch_directory(&start_path);
+        if app.index_enabled {
+            app.start_indexer();
+        }
         app
     }
 
+    // (Re)starts the background indexer over the current favorites
+    fn start_indexer(&mut self) {
+        self.stop_indexer();
+        let stop = Arc::new(AtomicBool::new(false));
+        self.index_generation += 1;
+        spawn_indexer(
+            self.favorites.clone(),
+            self.walk_options.clone(),
+            self.index_generation,
+            self.index_tx.clone(),
+            stop.clone(),
+        );
+        self.index_stop = Some(stop);
+    }
+
+    fn stop_indexer(&mut self) {
+        if let Some(stop) = self.index_stop.take() {
+            stop.store(true, Ordering::Relaxed);
+        }
+        self.index = None;
+    }
+
     fn open_in_terminal(&mut self) {
         #[cfg(target_os = "windows")]
         let result = Command::new("powershell")
@@ -293,50 +709,103 @@ impl ExplorerApp {
             },
             last_path: self.current_path.clone(),
             view_mode: self.view_mode,
+            walk_options: self.walk_options.clone(),
+            index_enabled: self.index_enabled,
+            recent_paths: self.recent_paths.clone(),
+            saved_searches: self.saved_searches.clone(),
+            auto_folder_sizes: self.auto_folder_sizes,
         };
         config.save();
     }
 
     fn load_preview(&mut self) {
+        let path = self
+            .selected_entry
+            .and_then(|idx| self.entries.get(idx))
+            .filter(|entry| entry.file_type == FileType::File)
+            .map(|entry| entry.path.clone());
+        self.load_preview_for(path);
+    }
+
+    fn load_preview_for(&mut self, path: Option<PathBuf>) {
+        if self.preview_is_dirty() {
+            // Unsaved edits: ask first, unless this is the same file again
+            if path != self.preview_path {
+                self.pending_preview = Some(path);
+            }
+            return;
+        }
+
         self.preview_data = None;
+        self.preview_path = None;
+        self.preview_mime = None;
+        self.preview_scroll_line = None;
+        self.preview_follow = false;
+        self.hex_cursor = 0;
+        self.hex_status = None;
+        self.cancel_hex_search();
         self.image_zoom = 1.0;
         self.image_offset = egui::Vec2::ZERO;
 
-        if let Some(idx) = self.selected_entry {
-            if let Some(entry) = self.entries.get(idx) {
-                if entry.file_type == FileType::File {
-                    let ext = entry
-                        .path
-                        .extension()
-                        .and_then(|s| s.to_str())
-                        .unwrap_or_default()
-                        .to_lowercase();
-
-                    match ext.as_str() {
-                        "txt" | "rs" | "toml" | "md" | "json" | "js" | "ts" | "py" | "c"
-                        | "cpp" | "h" | "go" | "zig" | "html" | "css" | "yaml" | "yml"
-                        | "sql" | "sh" | "bat" | "ps1" | "java" | "kt" | "php" | "rb"
-                        | "cc" | "hpp" | "jsx" | "tsx" | "cxx" | "lua" | "swift" | "dart" => {
-                            if let Ok(content) = fs::read_to_string(&entry.path) {
-                                // Limit preview size to 10KB
-                                let preview = if content.len() > 10240 {
-                                    format!("{}...", &content[..10240])
-                                } else {
-                                    content
-                                };
-                                self.preview_data = Some(PreviewData::Text(preview));
-                            }
-                        }
-                        "png" | "jpg" | "jpeg" | "gif" | "bmp" | "webp" | "svg" | "ico" | "tga"
-                        | "tiff" | "tif" | "pnm" | "dds" | "farbfeld" => {
-                            self.preview_data = Some(PreviewData::Image(entry.path.clone()));
-                        }
-                        "pdf" => {
-                            self.preview_data = Some(PreviewData::Pdf(entry.path.clone()));
+        if let Some(path) = path {
+            let Some(detected) = mime::detect(&path) else {
+                return;
+            };
+
+            match detected.kind {
+                ContentKind::Text if self.table_view && table::is_table(&path) => {
+                    match CsvTable::open(&path) {
+                        Ok(table) => self.preview_data = Some(PreviewData::Table(table)),
+                        Err(e) => self.error_message = Some(format!("Cannot open: {}", e)),
+                    }
+                }
+                ContentKind::Text => {
+                    let size = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+                    if size > FULL_TEXT_LIMIT {
+                        match PagedFile::open(&path) {
+                            Ok(paged) => self.preview_data = Some(PreviewData::Paged(paged)),
+                            Err(e) => self.error_message = Some(format!("Cannot open: {}", e)),
                         }
-                        _ => {}
+                    } else if let Ok(doc) = TextDocument::load(&path) {
+                        self.preview_data = Some(PreviewData::Text(doc));
                     }
                 }
+                ContentKind::Image if PREVIEW_IMAGE_TYPES.contains(&detected.mime) => {
+                    let meta = ImageMeta::read(&path);
+                    self.preview_data = Some(PreviewData::Image(path.clone(), meta));
+                }
+                ContentKind::Pdf => {
+                    self.preview_data = Some(PreviewData::Pdf(PdfPreview::new(path.clone())));
+                }
+                _ => match HexFile::open(&path) {
+                    Ok(file) => self.preview_data = Some(PreviewData::Hex(file)),
+                    Err(e) => self.error_message = Some(format!("Cannot open: {}", e)),
+                },
+            }
+
+            if self.preview_data.is_some() {
+                self.preview_path = Some(path);
+                self.preview_mime = Some(detected.mime);
+            }
+        }
+    }
+
+    fn select_match(&mut self, index: usize) {
+        let hit = self
+            .content_results
+            .as_ref()
+            .and_then(|results| results.get(index))
+            .map(|m| (m.path.clone(), m.line_number));
+
+        if let Some((path, line_number)) = hit {
+            self.selected_match = Some(index);
+            // Search hits are shown in the source, without changing the CSV/TSV default
+            let table_view = std::mem::replace(&mut self.table_view, false);
+            self.load_preview_for(Some(path.clone()));
+            self.table_view = table_view;
+            // Unsaved edits may have kept the previous preview up
+            if self.preview_path.as_ref() == Some(&path) {
+                self.preview_scroll_line = Some(line_number);
             }
         }
     }
@@ -349,264 +818,1285 @@ impl ExplorerApp {
     // --- Navigation ---
 
     fn navigate_to(&mut self, path: PathBuf, record_history: bool) {
+        self.open_directory(path, record_history, false);
+    }
+
+    // `from_cache` shows the last known listing of `path` straight away (history
+    // navigation); the loader then re-reads the folder only if it has changed.
+    fn open_directory(&mut self, path: PathBuf, record_history: bool, from_cache: bool) {
         if record_history && self.current_path != path {
             self.history.push(self.current_path.clone());
             self.forward_stack.clear();
+            self.remember_recent(path.clone());
         }
 
         self.current_path = path.clone();
         self.path_input = path.to_string_lossy().to_string();
         self.is_loading = true;
+        self.cancel_search();
         self.select_entry(None);
+        self.content_results = None;
+        self.selected_match = None;
         self.renaming_index = None;
         self.error_message = None;
-        let _ = self.load_req_tx.send(path);
-    }
-
-    fn go_back(&mut self) {
-        if let Some(prev) = self.history.pop() {
-            self.forward_stack.push(self.current_path.clone());
-            self.navigate_to(prev, false);
+        self.pending_select = None;
+        self.active_saved_search = None;
+        self.showing_search_results = false;
+        self.pending_fs_changes.clear();
+        self.sniffed.clear();
+        self.watch_directory(&path);
+
+        self.revalidating = false;
+        let cached = if from_cache {
+            self.listing_cache
+                .get(&path)
+                .map(|(mtime, entries)| (mtime, entries.to_vec()))
+        } else {
+            None
+        };
+        let cached_mtime = cached.map(|(mtime, entries)| {
+            self.entries = entries;
+            if !self.show_hidden {
+                self.entries.retain(|e| !e.is_hidden);
+            }
+            self.apply_folder_sizes();
+            self.sort_entries();
+            self.is_loading = false;
+            self.listing_incomplete = false;
+            self.revalidating = true;
+            mtime
+        });
+        self.folder_sizes_pending.clear();
+
+        self.load_cancel.store(true, Ordering::Relaxed);
+        self.load_cancel = Arc::new(AtomicBool::new(false));
+        self.load_generation += 1;
+        let _ = self.load_req_tx.send(LoadRequest {
+            generation: self.load_generation,
+            path,
+            cancel: self.load_cancel.clone(),
+            cached_mtime,
+        });
+        if cached_mtime.is_some() {
+            self.request_folder_sizes();
         }
     }
 
-    fn go_forward(&mut self) {
-        if let Some(next) = self.forward_stack.pop() {
-            self.history.push(self.current_path.clone());
-            self.navigate_to(next, false);
+    fn handle_load_update(&mut self, update: LoadUpdate) {
+        // Answer to a superseded request, or search results took over the view
+        if update.generation != self.load_generation
+            || update.path != self.current_path
+            || self.active_saved_search.is_some()
+            || self.showing_search_results
+        {
+            return;
         }
-    }
 
-    fn go_up(&mut self) {
-        if let Some(parent) = self.current_path.parent() {
-            self.navigate_to(parent.to_path_buf(), true);
+        let replaces_cached = self.revalidating && !matches!(update.event, LoadEvent::Unchanged);
+        if self.is_loading || replaces_cached {
+            // First news of this listing, drop the previous (or cached) one
+            self.is_loading = false;
+            self.revalidating = false;
+            self.listing_incomplete = true;
+            self.entries.clear();
+            self.load_positions.clear();
+            self.listing_hidden.clear();
         }
-    }
 
-    fn refresh(&mut self) {
-        self.navigate_to(self.current_path.clone(), false);
+        match update.event {
+            LoadEvent::Listing(ListingEvent::Entries(batch)) => {
+                for entry in batch {
+                    if entry.is_hidden && !self.show_hidden {
+                        // Still cached with the listing, for when hidden files are shown
+                        self.listing_hidden.insert(entry.path.clone(), entry);
+                    } else {
+                        self.entries.push(entry);
+                    }
+                }
+            }
+            LoadEvent::Listing(ListingEvent::Listed) => {
+                let selected = self.selected_path();
+                self.sort_entries();
+                self.restore_selection(selected);
+                if let Some(path) = self.pending_select.take() {
+                    let index = self.entries.iter().position(|e| e.path == path);
+                    self.select_entry(index);
+                }
+                self.load_positions = self
+                    .entries
+                    .iter()
+                    .enumerate()
+                    .map(|(i, e)| (e.path.clone(), i))
+                    .collect();
+            }
+            LoadEvent::Listing(ListingEvent::Metadata(batch)) => {
+                for entry in batch {
+                    if let Some(hidden) = self.listing_hidden.get_mut(&entry.path) {
+                        *hidden = entry;
+                        continue;
+                    }
+                    // Positions go stale if the user re-sorted in the meantime
+                    let position = self
+                        .load_positions
+                        .get(&entry.path)
+                        .copied()
+                        .filter(|&i| self.entries.get(i).is_some_and(|e| e.path == entry.path))
+                        .or_else(|| self.entries.iter().position(|e| e.path == entry.path));
+                    if let Some(i) = position {
+                        self.entries[i] = entry;
+                    }
+                }
+            }
+            LoadEvent::Unchanged => {
+                self.revalidating = false;
+            }
+            LoadEvent::Done(mtime) => {
+                self.listing_incomplete = false;
+                self.load_positions.clear();
+                if let Some(mtime) = mtime {
+                    let mut listing = self.entries.clone();
+                    listing.extend(self.listing_hidden.drain().map(|(_, e)| e));
+                    self.listing_cache.insert(update.path, mtime, listing);
+                }
+                self.listing_hidden.clear();
+                let selected = self.selected_path();
+                if !self.show_hidden {
+                    self.entries.retain(|e| !e.is_hidden);
+                }
+                self.apply_folder_sizes();
+                // Size and date ordering needed the metadata
+                self.sort_entries();
+                self.restore_selection(selected);
+                self.request_folder_sizes();
+            }
+            LoadEvent::Failed(e) => {
+                self.listing_incomplete = false;
+                self.error_message = Some(e);
+            }
+        }
     }
 
-    fn apply_theme(&self, ctx: &egui::Context) {
-        let visuals = match self.theme {
-            Theme::Dark => egui::Visuals::dark(),
-            Theme::Light => egui::Visuals::light(),
-            Theme::Mocha => {
-                // Catppuccin Mocha inspired, but tweaked for better contrast
-                let mut visuals = egui::Visuals::dark();
-                visuals.panel_fill = egui::Color32::from_rgb(30, 30, 46); // Base
-                visuals.window_fill = egui::Color32::from_rgb(30, 30, 46);
-                visuals.extreme_bg_color = egui::Color32::from_rgb(24, 24, 37); // Mantle - Darker background for inputs/lists
-
-                // Non-interactive widgets (labels, etc)
-                visuals.widgets.noninteractive.bg_fill = egui::Color32::from_rgb(30, 30, 46);
-                visuals.widgets.noninteractive.fg_stroke =
-                    egui::Stroke::new(1.0, egui::Color32::from_rgb(205, 214, 244)); // Text - White-ish
-
-                // Inactive widgets (buttons)
-                visuals.widgets.inactive.bg_fill = egui::Color32::from_rgb(49, 50, 68); // Surface0
-                visuals.widgets.inactive.fg_stroke =
-                    egui::Stroke::new(1.0, egui::Color32::from_rgb(205, 214, 244));
-
-                // Hovered widgets
-                visuals.widgets.hovered.bg_fill = egui::Color32::from_rgb(88, 91, 112); // Surface2
-                visuals.widgets.hovered.fg_stroke =
-                    egui::Stroke::new(1.0, egui::Color32::WHITE);
-
-                // Active widgets (clicked)
-                visuals.widgets.active.bg_fill = egui::Color32::from_rgb(116, 199, 236); // Sapphire
-                visuals.widgets.active.fg_stroke =
-                    egui::Stroke::new(1.0, egui::Color32::from_rgb(30, 30, 46)); // Dark text on active
-
-                // Selection
-                visuals.selection.bg_fill =
-                    egui::Color32::from_rgb(137, 180, 250).gamma_multiply(0.4); // Blue selection, transparent
-                visuals.selection.stroke =
-                    egui::Stroke::new(1.0, egui::Color32::from_rgb(137, 180, 250));
+    // --- Live Refresh ---
 
-                visuals.hyperlink_color = egui::Color32::from_rgb(137, 220, 235); // Sapphire
-                visuals
-            }
+    fn watch_directory(&mut self, path: &Path) {
+        let Some(watcher) = &mut self.watcher else {
+            return;
         };
-        ctx.set_visuals(visuals);
+        if self.watched_path.as_deref() == Some(path) {
+            return;
+        }
+        if let Some(old) = self.watched_path.take() {
+            let _ = watcher.unwatch(&old);
+        }
+        match watcher.watch(path, RecursiveMode::NonRecursive) {
+            Ok(()) => self.watched_path = Some(path.to_path_buf()),
+            Err(e) => log::warn!("Cannot watch {}: {}", path.display(), e),
+        }
     }
 
-    fn sort_entries(&mut self) {
-        self.entries.sort_by(|a, b| {
-            let ordering = match self.sort_column {
-                SortColumn::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
-                SortColumn::Size => a.size.cmp(&b.size),
-                SortColumn::Modified => a.modified.cmp(&b.modified),
-            };
+    // Patches `entries` for paths reported by the watcher: re-stat each one and
+    // insert, update or drop it, so the selection and scroll position survive.
+    fn apply_fs_changes(&mut self) {
+        let listing_shown = !self.is_loading
+            && !self.listing_incomplete
+            && !self.showing_search_results
+            && self.active_saved_search.is_none()
+            && self.renaming_index.is_none(); // Indices must stay put while renaming
+        if self.pending_fs_changes.is_empty() || !listing_shown {
+            return;
+        }
 
-            if self.sort_order == SortOrder::Descending {
-                ordering.reverse()
-            } else {
-                ordering
+        let mut changed = false;
+        let selected = self.selected_path();
+        for path in std::mem::take(&mut self.pending_fs_changes) {
+            if path.parent() != Some(self.current_path.as_path()) {
+                continue;
             }
-        });
+            let position = self.entries.iter().position(|e| e.path == path);
+            let fresh = fs::symlink_metadata(&path).ok().map(|metadata| {
+                let name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+                // Sniffed again when next in view, the content may have changed
+                self.sniffed.remove(&path);
+                file_entry_from_metadata(name, path.clone(), &metadata)
+            });
 
-        // Always keep directories on top
-        self.entries.sort_by(|a, b| {
-            match (
-                a.file_type == FileType::Directory,
-                b.file_type == FileType::Directory,
-            ) {
-                (true, false) => std::cmp::Ordering::Less,
-                (false, true) => std::cmp::Ordering::Greater,
-                _ => std::cmp::Ordering::Equal,
+            match (position, fresh) {
+                (Some(i), Some(entry)) if self.show_hidden || !entry.is_hidden => {
+                    self.entries[i] = entry;
+                }
+                (None, Some(entry)) if self.show_hidden || !entry.is_hidden => {
+                    self.entries.push(entry);
+                }
+                (Some(i), _) => {
+                    self.entries.remove(i);
+                }
+                (None, _) => continue,
             }
-        });
-    }
+            changed = true;
+        }
 
-    fn create_new_item(&mut self) {
-        if self.new_item_name.is_empty() {
-            return;
+        if changed {
+            self.apply_folder_sizes();
+            self.sort_entries();
+            self.restore_selection(selected);
         }
+    }
 
-        let result = if self.create_folder {
-            create_directory(&self.current_path, &self.new_item_name)
-        } else {
-            create_file(&self.current_path, &self.new_item_name)
-        };
+    // --- Folder Sizes ---
 
-        if let Err(e) = result {
-            self.error_message = Some(format!("Creation failed: {}", e));
-        } else {
-            self.refresh();
+    fn compute_folder_size(&mut self, path: PathBuf) {
+        if self.folder_sizes_pending.insert(path.clone()) {
+            let _ = self.size_req_tx.send((path, self.load_cancel.clone()));
         }
-        self.creation_popup_open = false;
-        self.new_item_name.clear();
     }
 
-    fn toggle_favorite(&mut self) {
-        if self.favorites.contains(&self.current_path) {
-            self.favorites.retain(|p| p != &self.current_path);
-        } else {
-            self.favorites.push(self.current_path.clone());
+    // Queues every listed folder without a known size, if automatic sizing is on
+    fn request_folder_sizes(&mut self) {
+        if !self.auto_folder_sizes || self.showing_search_results {
+            return;
+        }
+        let missing: Vec<PathBuf> = self
+            .entries
+            .iter()
+            .filter(|e| {
+                e.file_type == FileType::Directory && !self.folder_sizes.contains_key(&e.path)
+            })
+            .map(|e| e.path.clone())
+            .collect();
+        for path in missing {
+            self.compute_folder_size(path);
         }
-        self.save_state();
     }
 
-    fn save_current_file(&mut self) {
-        if let Some(PreviewData::Text(content)) = &self.preview_data {
-            if let Some(idx) = self.selected_entry {
-                if let Some(entry) = self.entries.get(idx) {
-                    if let Err(e) = std::fs::write(&entry.path, content) {
-                        self.error_message = Some(format!("Failed to save: {}", e));
-                    }
+    // Directories are listed with size 0, fill in what we know so size sorting works
+    fn apply_folder_sizes(&mut self) {
+        for entry in &mut self.entries {
+            if entry.file_type == FileType::Directory {
+                if let Some(size) = self.folder_sizes.get(&entry.path) {
+                    entry.size = size.bytes;
                 }
             }
         }
     }
 
-    // --- Operations ---
+    // --- Disk Usage ---
 
-    fn open_entry(&mut self, index: usize) {
-        if let Some(entry) = self.entries.get(index) {
-            match entry.file_type {
-                FileType::Directory => {
-                    self.navigate_to(entry.path.clone(), true);
-                }
-                FileType::File | FileType::Symlink => {
-                    if let Err(e) = open::that(&entry.path) {
-                        self.error_message = Some(format!("Failed to open: {}", e));
-                    }
-                }
-                FileType::Unknown => {}
+    fn start_usage_scan(&mut self) {
+        self.cancel_usage_scan();
+        self.usage_id += 1;
+        let cancel = Arc::new(AtomicBool::new(false));
+        self.usage_cancel = Some(cancel.clone());
+        self.usage_scanning = Some(self.current_path.clone());
+
+        let (id, root, tx) = (self.usage_id, self.current_path.clone(), self.usage_tx.clone());
+        thread::spawn(move || {
+            if let Some(node) = scan_usage(&root, &cancel) {
+                let _ = tx.send((id, node));
             }
-        }
+        });
     }
 
-    fn start_rename(&mut self) {
-        if let Some(idx) = self.selected_entry {
-            if let Some(entry) = self.entries.get(idx) {
-                self.renaming_index = Some(idx);
-                self.rename_buffer = entry.name.clone();
-            }
+    fn cancel_usage_scan(&mut self) {
+        if let Some(cancel) = self.usage_cancel.take() {
+            cancel.store(true, Ordering::Relaxed);
         }
+        self.usage_scanning = None;
     }
 
-    fn confirm_rename(&mut self) {
-        if let Some(idx) = self.renaming_index {
-            if let Some(entry) = self.entries.get(idx) {
-                if !self.rename_buffer.is_empty() && self.rename_buffer != entry.name {
-                    if let Err(e) = rename_entry(&entry.path, &self.rename_buffer) {
-                        self.error_message = Some(format!("Rename failed: {}", e));
-                    } else {
-                        self.refresh();
-                    }
-                }
-            }
+    // Scans current_path unless the last (or running) scan already includes it,
+    // so drilling down into a scanned folder is instant
+    fn ensure_usage_scan(&mut self) {
+        let covered = |root: &PathBuf| self.current_path.starts_with(root);
+        if !self.usage.as_ref().is_some_and(|u| covered(&u.path))
+            && !self.usage_scanning.as_ref().is_some_and(covered)
+        {
+            self.start_usage_scan();
         }
-        self.renaming_index = None;
     }
 
-    fn delete_selected(&mut self) {
-        if let Some(idx) = self.selected_entry {
-            if let Some(entry) = self.entries.get(idx) {
-                if let Err(e) = delete_entry(&entry.path) {
-                    self.error_message = Some(format!("Delete failed: {}", e));
-                } else {
-                    self.select_entry(None);
-                    self.refresh();
+    fn remove_usage_path(&mut self, path: &Path, to_trash: bool) {
+        let result = if to_trash {
+            trash_entry(path)
+        } else {
+            delete_entry(path)
+        };
+        match result {
+            Ok(()) => {
+                if let Some(usage) = &mut self.usage {
+                    usage.remove(path);
                 }
+                self.refresh();
             }
+            Err(e) => self.error_message = Some(format!("Delete failed: {}", e)),
         }
     }
 
-    fn get_icon_for_entry(&self, entry: &FileEntry) -> (&'static str, egui::Color32) {
-        match entry.file_type {
-            FileType::Directory => ("📁", egui::Color32::from_rgb(249, 226, 175)), // Yellow (Peach-ish)
-            FileType::Symlink => ("🔗", egui::Color32::from_rgb(148, 226, 213)),   // Teal
-            FileType::File => {
-                let ext = entry
-                    .path
-                    .extension()
-                    .and_then(|s| s.to_str())
-                    .unwrap_or_default()
-                    .to_lowercase();
-
-                match ext.as_str() {
-                    // Code / Config - Blue/Lavender
-                    "rs" | "go" | "c" | "cpp" | "h" | "hpp" | "cc" | "cxx" | "zig" => {
-                        ("🦀", egui::Color32::from_rgb(203, 166, 247)) // Mauve for low level
+    fn show_usage_view(&mut self, ui: &mut egui::Ui) {
+        let mut navigate = None;
+        let mut open = None;
+        let mut remove = None;
+        let mut rescan = false;
+
+        let node = self.usage.as_ref().and_then(|u| u.find(&self.current_path));
+        ui.horizontal(|ui| {
+            if let Some(node) = node {
+                ui.strong(format!("💽 {}", node.name));
+                ui.label(format_size(node.size, DECIMAL));
+            }
+            if self.usage_scanning.is_some() {
+                ui.spinner();
+                ui.label("Scanning...");
+            }
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui.button("⟳ Rescan").clicked() {
+                    rescan = true;
+                }
+            });
+        });
+        ui.separator();
+
+        let Some(node) = node else {
+            if self.usage_scanning.is_none() {
+                ui.label("This folder was not part of the last scan.");
+            }
+            if rescan {
+                self.start_usage_scan();
+            }
+            return;
+        };
+
+        egui::SidePanel::right("usage_largest")
+            .resizable(true)
+            .default_width(260.0)
+            .show_inside(ui, |ui| {
+                ui.heading("Largest Files");
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    for file in node.largest_files(USAGE_LARGEST_FILES) {
+                        ui.horizontal(|ui| {
+                            if ui.small_button("🗑").on_hover_text("Move to Trash").clicked() {
+                                remove = Some((file.path.clone(), true));
+                            }
+                            if ui.small_button("✖").on_hover_text("Delete").clicked() {
+                                remove = Some((file.path.clone(), false));
+                            }
+                            ui.label(format_size(file.size, DECIMAL));
+                            let label = egui::Label::new(&file.name)
+                                .truncate()
+                                .sense(egui::Sense::click());
+                            let resp = ui.add(label).on_hover_text(file.path.to_string_lossy());
+                            if resp.double_clicked() {
+                                open = Some(file.path.clone());
+                            }
+                        });
+                    }
+                });
+            });
+
+        // One level of the tree at a time, folders drill down on click
+        let (rect, _) = ui.allocate_exact_size(ui.available_size(), egui::Sense::hover());
+        let sizes: Vec<u64> = node.children.iter().map(|c| c.size).collect();
+        let painter = ui.painter_at(rect);
+        for (child, cell) in node.children.iter().zip(treemap(&sizes, rect)) {
+            if cell.width() < 2.0 || cell.height() < 2.0 {
+                continue;
+            }
+            let entry = FileEntry {
+                name: child.name.clone(),
+                path: child.path.clone(),
+                file_type: if child.is_dir { FileType::Directory } else { FileType::File },
+                size: child.size,
+                modified: 0,
+                is_hidden: false,
+                category: file_category(&child.path),
+            };
+            let (icon, color) = self.get_icon_for_entry(&entry);
+
+            let resp = ui.interact(cell, ui.id().with(&child.path), egui::Sense::click());
+            let fill = if resp.hovered() { 0.8 } else { 0.5 };
+            painter.rect_filled(cell.shrink(1.0), 3.0, color.gamma_multiply(fill));
+            if cell.width() > 60.0 && cell.height() > 34.0 {
+                painter.with_clip_rect(cell.shrink(2.0)).text(
+                    cell.left_top() + egui::vec2(5.0, 4.0),
+                    egui::Align2::LEFT_TOP,
+                    format!("{} {}\n{}", icon, child.name, format_size(child.size, DECIMAL)),
+                    egui::FontId::proportional(12.0),
+                    egui::Color32::BLACK,
+                );
+            }
+
+            let resp = resp.on_hover_text(format!(
+                "{}\n{}",
+                child.path.display(),
+                format_size(child.size, DECIMAL)
+            ));
+            if resp.clicked() && child.is_dir {
+                navigate = Some(child.path.clone());
+            }
+            if resp.double_clicked() && !child.is_dir {
+                open = Some(child.path.clone());
+            }
+            resp.context_menu(|ui| {
+                if ui.button("Open").clicked() {
+                    open = Some(child.path.clone());
+                    ui.close_menu();
+                }
+                ui.separator();
+                if ui.button("🗑 Move to Trash").clicked() {
+                    remove = Some((child.path.clone(), true));
+                    ui.close_menu();
+                }
+                if ui.button("✖ Delete").clicked() {
+                    remove = Some((child.path.clone(), false));
+                    ui.close_menu();
+                }
+            });
+        }
+
+        if rescan {
+            self.start_usage_scan();
+        }
+        if let Some(path) = navigate {
+            self.navigate_to(path, true);
+        }
+        if let Some(path) = open {
+            self.open_path(&path);
+        }
+        if remove.is_some() {
+            self.usage_confirm = remove;
+        }
+    }
+
+    // Deleting from the usage view targets the biggest items, so it asks first
+    fn show_usage_confirm(&mut self, ctx: &egui::Context) {
+        let Some((path, to_trash)) = self.usage_confirm.clone() else {
+            return;
+        };
+        let mut close = false;
+        egui::Window::new(if to_trash { "Move to Trash" } else { "Delete" })
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                let verb = if to_trash { "Move to the trash" } else { "Permanently delete" };
+                ui.label(format!("{} \"{}\"?", verb, path.display()));
+                ui.horizontal(|ui| {
+                    if ui.button("Yes").clicked() {
+                        self.remove_usage_path(&path, to_trash);
+                        close = true;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        close = true;
+                    }
+                });
+            });
+        if close {
+            self.usage_confirm = None;
+        }
+    }
+
+    fn handle_folder_size(&mut self, path: PathBuf, size: FolderSize) {
+        self.folder_sizes_pending.remove(&path);
+        self.folder_sizes.insert(path.clone(), size);
+        if let Some(entry) = self.entries.iter_mut().find(|e| e.path == path) {
+            entry.size = size.bytes;
+            if self.sort_column == SortColumn::Size {
+                let selected = self.selected_path();
+                self.sort_entries();
+                self.restore_selection(selected);
+            }
+        }
+    }
+
+    fn selected_path(&self) -> Option<PathBuf> {
+        self.selected_entry
+            .and_then(|idx| self.entries.get(idx))
+            .map(|e| e.path.clone())
+    }
+
+    // Re-point `selected_entry` after `entries` was reordered
+    fn restore_selection(&mut self, selected: Option<PathBuf>) {
+        self.selected_entry =
+            selected.and_then(|path| self.entries.iter().position(|e| e.path == path));
+    }
+
+    // --- Saved Searches ---
+
+    fn can_save_search(&self) -> bool {
+        !self.content_search && (!self.search_query.is_empty() || self.search_filter.is_active())
+    }
+
+    fn save_current_search(&mut self) {
+        let name = self.save_search_name.trim().to_string();
+        if name.is_empty() || !self.can_save_search() {
+            return;
+        }
+        self.saved_searches.push(SavedSearch {
+            name,
+            root: self.current_path.clone(),
+            query: self.search_query.clone(),
+            filter: self.search_filter.clone(),
+        });
+        self.save_search_popup_open = false;
+        self.save_search_name.clear();
+        self.save_state();
+    }
+
+    fn remove_saved_search(&mut self, index: usize) {
+        if index < self.saved_searches.len() {
+            self.saved_searches.remove(index);
+            match self.active_saved_search {
+                Some(active) if active == index => self.active_saved_search = None,
+                Some(active) if active > index => self.active_saved_search = Some(active - 1),
+                _ => {}
+            }
+            self.save_state();
+        }
+    }
+
+    // Shows the saved search's live results in place of a directory listing
+    fn open_saved_search(&mut self, index: usize) {
+        let Some(saved) = self.saved_searches.get(index) else {
+            return;
+        };
+        let root = saved.root.clone();
+
+        if self.active_saved_search != Some(index) {
+            self.history.push(self.current_path.clone());
+            self.forward_stack.clear();
+        }
+        self.current_path = root.clone();
+        self.path_input = root.to_string_lossy().to_string();
+        self.is_loading = false;
+        self.content_results = None;
+        self.selected_match = None;
+        self.renaming_index = None;
+        self.error_message = None;
+        self.pending_select = None;
+        self.active_saved_search = Some(index);
+        self.run_saved_search();
+    }
+
+    fn run_saved_search(&mut self) {
+        let Some(saved) = self.active_saved_search.and_then(|i| self.saved_searches.get(i)) else {
+            return;
+        };
+        // Runs with its own query and filter, leaving the search bar as it was
+        let (query, filter) = (saved.query.clone(), saved.filter.clone());
+        self.search_recursive(query, filter);
+    }
+
+    fn remember_recent(&mut self, path: PathBuf) {
+        self.recent_paths.retain(|p| p != &path);
+        self.recent_paths.insert(0, path);
+        self.recent_paths.truncate(RECENT_LIMIT);
+    }
+
+    // --- Quick Open ---
+
+    fn open_quick_open(&mut self) {
+        self.quick_open = true;
+        self.quick_open_focus = true;
+        self.quick_open_query.clear();
+        self.quick_open_selected = 0;
+        self.quick_open_id += 1;
+
+        // Prefer the index, otherwise walk the current folder in the background
+        if let Some(index) = self.index.as_ref().filter(|i| i.covers(&self.current_path)) {
+            self.quick_open_candidates = index
+                .entries_under(&self.current_path)
+                .map(|e| (e.path.clone(), e.file_type == FileType::Directory))
+                .collect();
+            self.quick_open_scanning = false;
+        } else {
+            self.quick_open_candidates.clear();
+            self.quick_open_scanning = true;
+            let id = self.quick_open_id;
+            let tx = self.quick_open_tx.clone();
+            let root = self.current_path.clone();
+            let options = self.walk_options.clone();
+            thread::spawn(move || {
+                let paths = collect_paths(&root, &options, QUICK_OPEN_SCAN_LIMIT).unwrap_or_default();
+                let _ = tx.send((id, paths));
+            });
+        }
+        self.update_quick_open_results();
+    }
+
+    fn update_quick_open_results(&mut self) {
+        let matcher = SkimMatcherV2::default();
+        let query = &self.quick_open_query;
+        let root = &self.current_path;
+
+        let mut scored: Vec<(i64, &(PathBuf, bool))> = self
+            .quick_open_candidates
+            .iter()
+            .filter_map(|candidate| {
+                let rel = candidate.0.strip_prefix(root).unwrap_or(&candidate.0);
+                let score = if query.is_empty() {
+                    0
+                } else {
+                    matcher.fuzzy_match(&rel.to_string_lossy(), query)?
+                };
+                // Recently used paths float up, the most recent the most
+                let recency = self
+                    .recent_paths
+                    .iter()
+                    .position(|p| p == &candidate.0)
+                    .map_or(0, |pos| (RECENT_LIMIT - pos) as i64 * 2);
+                Some((score + recency, candidate))
+            })
+            .collect();
+
+        scored.sort_by(|a, b| {
+            b.0.cmp(&a.0)
+                .then(a.1 .0.as_os_str().len().cmp(&b.1 .0.as_os_str().len()))
+        });
+        self.quick_open_results = scored
+            .into_iter()
+            .take(QUICK_OPEN_RESULTS)
+            .map(|(_, c)| c.clone())
+            .collect();
+        self.quick_open_selected = 0;
+    }
+
+    fn accept_quick_open(&mut self, index: usize) {
+        let Some((path, is_dir)) = self.quick_open_results.get(index).cloned() else {
+            return;
+        };
+        self.quick_open = false;
+
+        if is_dir {
+            self.navigate_to(path, true);
+        } else if let Some(parent) = path.parent() {
+            self.navigate_to(parent.to_path_buf(), true);
+            self.remember_recent(path.clone());
+            self.pending_select = Some(path);
+        }
+        self.save_state();
+    }
+
+    fn show_quick_open(&mut self, ctx: &egui::Context) {
+        let mut accept = None;
+        let mut close = false;
+
+        egui::Window::new("Go to File")
+            .title_bar(false)
+            .collapsible(false)
+            .resizable(false)
+            .fixed_size([500.0, 0.0])
+            .anchor(egui::Align2::CENTER_TOP, egui::vec2(0.0, 60.0))
+            .show(ctx, |ui| {
+                let resp = ui.add(
+                    egui::TextEdit::singleline(&mut self.quick_open_query)
+                        .hint_text("Go to file...")
+                        .desired_width(f32::INFINITY),
+                );
+                if self.quick_open_focus {
+                    resp.request_focus();
+                    self.quick_open_focus = false;
+                }
+                if resp.changed() {
+                    self.update_quick_open_results();
+                }
+
+                let count = self.quick_open_results.len();
+                ui.input_mut(|i| {
+                    if i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown) && count > 0 {
+                        self.quick_open_selected = (self.quick_open_selected + 1).min(count - 1);
+                    }
+                    if i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp) {
+                        self.quick_open_selected = self.quick_open_selected.saturating_sub(1);
+                    }
+                    if i.key_pressed(egui::Key::Enter) {
+                        accept = Some(self.quick_open_selected);
+                    }
+                    if i.key_pressed(egui::Key::Escape) {
+                        close = true;
+                    }
+                });
+
+                ui.separator();
+                if self.quick_open_scanning {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label("Scanning...");
+                    });
+                }
+                for (i, (path, is_dir)) in self.quick_open_results.iter().enumerate() {
+                    let rel = path.strip_prefix(&self.current_path).unwrap_or(path);
+                    let icon = if *is_dir { "📁" } else { "📄" };
+                    let resp = ui.selectable_label(
+                        i == self.quick_open_selected,
+                        format!("{} {}", icon, rel.to_string_lossy()),
+                    );
+                    if resp.clicked() {
+                        accept = Some(i);
+                    }
+                }
+            });
+
+        if close {
+            self.quick_open = false;
+        } else if let Some(i) = accept {
+            self.accept_quick_open(i);
+        }
+    }
+
+    fn go_back(&mut self) {
+        if let Some(prev) = self.history.pop() {
+            self.forward_stack.push(self.current_path.clone());
+            self.open_directory(prev, false, true);
+        }
+    }
+
+    fn go_forward(&mut self) {
+        if let Some(next) = self.forward_stack.pop() {
+            self.history.push(self.current_path.clone());
+            self.open_directory(next, false, true);
+        }
+    }
+
+    fn go_up(&mut self) {
+        if let Some(parent) = self.current_path.parent() {
+            self.navigate_to(parent.to_path_buf(), true);
+        }
+    }
+
+    fn refresh(&mut self) {
+        if self.active_saved_search.is_some() {
+            // Virtual folders refresh by re-running their search
+            self.run_saved_search();
+            return;
+        }
+        // Sizes of the subfolders may be out of date too
+        let current = self.current_path.clone();
+        self.folder_sizes.retain(|path, _| path.parent() != Some(current.as_path()));
+        self.navigate_to(current, false);
+    }
+
+    fn apply_theme(&self, ctx: &egui::Context) {
+        let visuals = match self.theme {
+            Theme::Dark => egui::Visuals::dark(),
+            Theme::Light => egui::Visuals::light(),
+            Theme::Mocha => {
+                // Catppuccin Mocha inspired, but tweaked for better contrast
+                let mut visuals = egui::Visuals::dark();
+                visuals.panel_fill = egui::Color32::from_rgb(30, 30, 46); // Base
+                visuals.window_fill = egui::Color32::from_rgb(30, 30, 46);
+                visuals.extreme_bg_color = egui::Color32::from_rgb(24, 24, 37); // Mantle - Darker background for inputs/lists
+
+                // Non-interactive widgets (labels, etc)
+                visuals.widgets.noninteractive.bg_fill = egui::Color32::from_rgb(30, 30, 46);
+                visuals.widgets.noninteractive.fg_stroke =
+                    egui::Stroke::new(1.0, egui::Color32::from_rgb(205, 214, 244)); // Text - White-ish
+
+                // Inactive widgets (buttons)
+                visuals.widgets.inactive.bg_fill = egui::Color32::from_rgb(49, 50, 68); // Surface0
+                visuals.widgets.inactive.fg_stroke =
+                    egui::Stroke::new(1.0, egui::Color32::from_rgb(205, 214, 244));
+
+                // Hovered widgets
+                visuals.widgets.hovered.bg_fill = egui::Color32::from_rgb(88, 91, 112); // Surface2
+                visuals.widgets.hovered.fg_stroke =
+                    egui::Stroke::new(1.0, egui::Color32::WHITE);
+
+                // Active widgets (clicked)
+                visuals.widgets.active.bg_fill = egui::Color32::from_rgb(116, 199, 236); // Sapphire
+                visuals.widgets.active.fg_stroke =
+                    egui::Stroke::new(1.0, egui::Color32::from_rgb(30, 30, 46)); // Dark text on active
+
+                // Selection
+                visuals.selection.bg_fill =
+                    egui::Color32::from_rgb(137, 180, 250).gamma_multiply(0.4); // Blue selection, transparent
+                visuals.selection.stroke =
+                    egui::Stroke::new(1.0, egui::Color32::from_rgb(137, 180, 250));
+
+                visuals.hyperlink_color = egui::Color32::from_rgb(137, 220, 235); // Sapphire
+                visuals
+            }
+        };
+        ctx.set_visuals(visuals);
+    }
+
+    fn sort_entries(&mut self) {
+        self.entries.sort_by(|a, b| {
+            let ordering = match self.sort_column {
+                SortColumn::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+                SortColumn::Size => a.size.cmp(&b.size),
+                SortColumn::Modified => a.modified.cmp(&b.modified),
+            };
+
+            if self.sort_order == SortOrder::Descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        });
+
+        // Always keep directories on top
+        self.entries.sort_by(|a, b| {
+            match (
+                a.file_type == FileType::Directory,
+                b.file_type == FileType::Directory,
+            ) {
+                (true, false) => std::cmp::Ordering::Less,
+                (false, true) => std::cmp::Ordering::Greater,
+                _ => std::cmp::Ordering::Equal,
+            }
+        });
+    }
+
+    fn toggle_sort(&mut self, column: SortColumn) {
+        if self.sort_column == column {
+            self.sort_order = if self.sort_order == SortOrder::Ascending {
+                SortOrder::Descending
+            } else {
+                SortOrder::Ascending
+            };
+        } else {
+            self.sort_column = column;
+            self.sort_order = SortOrder::Ascending;
+        }
+        let selected = self.selected_path();
+        self.sort_entries();
+        self.restore_selection(selected);
+        self.save_state();
+    }
+
+    fn create_new_item(&mut self) {
+        if self.new_item_name.is_empty() {
+            return;
+        }
+
+        let result = if self.create_folder {
+            create_directory(&self.current_path, &self.new_item_name)
+        } else {
+            create_file(&self.current_path, &self.new_item_name)
+        };
+
+        if let Err(e) = result {
+            self.error_message = Some(format!("Creation failed: {}", e));
+        } else {
+            self.refresh();
+        }
+        self.creation_popup_open = false;
+        self.new_item_name.clear();
+    }
+
+    fn toggle_favorite(&mut self) {
+        if self.favorites.contains(&self.current_path) {
+            self.favorites.retain(|p| p != &self.current_path);
+        } else {
+            self.favorites.push(self.current_path.clone());
+        }
+        self.save_state();
+        if self.index_enabled {
+            self.start_indexer();
+        }
+    }
+
+    fn preview_is_dirty(&self) -> bool {
+        matches!(&self.preview_data, Some(PreviewData::Text(doc)) if doc.is_dirty())
+    }
+
+    // Dimensions, format and EXIF details below the image preview
+    fn image_info(ui: &mut egui::Ui, meta: &ImageMeta) {
+        egui::CollapsingHeader::new("Image Info")
+            .default_open(true)
+            .show(ui, |ui| {
+                egui::Grid::new("image_info_grid").striped(true).show(ui, |ui| {
+                    for (label, value) in meta.summary() {
+                        ui.label(label);
+                        ui.label(value);
+                        ui.end_row();
+                    }
+                    if let Some((lat, lon)) = meta.gps {
+                        ui.label("GPS");
+                        let url = format!(
+                            "https://www.openstreetmap.org/?mlat={lat}&mlon={lon}#map=15/{lat}/{lon}"
+                        );
+                        ui.hyperlink_to(format!("{:.5}, {:.5}", lat, lon), url)
+                            .on_hover_text("Show on OpenStreetMap");
+                        ui.end_row();
+                    }
+                });
+            });
+    }
+
+    // Page view with paging and zoom; pages are rendered off the UI thread
+    fn show_pdf_preview(
+        ui: &mut egui::Ui,
+        pdf: &mut PdfPreview,
+        zoom: &mut f32,
+        tx: &Sender<PdfRequest>,
+    ) {
+        if let Some(error) = &pdf.error {
+            ui.centered_and_justified(|ui| {
+                ui.vertical(|ui| {
+                    ui.label("📄 PDF File");
+                    let name = pdf.path.file_name().unwrap_or_default();
+                    ui.label(name.to_string_lossy());
+                    ui.weak(error);
+                    ui.add_space(10.0);
+                    if ui.button("Open with Default App").clicked() {
+                        let _ = open::that(&pdf.path);
+                    }
+                });
+            });
+            return;
+        }
+
+        // Same pinch/ctrl+scroll zoom as images
+        let delta = ui.input(|i| i.zoom_delta());
+        if delta != 1.0 {
+            *zoom = (*zoom * delta).clamp(0.1, 5.0);
+        }
+
+        let page_count = pdf.info.as_ref().map_or(0, |info| info.page_count);
+        ui.horizontal(|ui| {
+            if ui.add_enabled(pdf.page > 0, egui::Button::new("◀")).clicked() {
+                pdf.page -= 1;
+            }
+            ui.label(format!("Page {} of {}", pdf.page + 1, page_count));
+            let has_next = pdf.page + 1 < page_count;
+            if ui.add_enabled(has_next, egui::Button::new("▶")).clicked() {
+                pdf.page += 1;
+            }
+            ui.weak(format!("{:.0}%", *zoom * 100.0))
+                .on_hover_text("Ctrl+scroll to zoom");
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui.button("Open").on_hover_text("Open with Default App").clicked() {
+                    let _ = open::that(&pdf.path);
+                }
+            });
+        });
+        if let Some(info) = &pdf.info {
+            egui::CollapsingHeader::new("Document Info").show(ui, |ui| {
+                egui::Grid::new("pdf_info").striped(true).show(ui, |ui| {
+                    for (label, value) in [
+                        ("Title", &info.title),
+                        ("Author", &info.author),
+                        ("Subject", &info.subject),
+                        ("Created", &info.created),
+                        ("Creator", &info.creator),
+                        ("Producer", &info.producer),
+                    ] {
+                        if let Some(value) = value {
+                            ui.label(label);
+                            ui.label(value);
+                            ui.end_row();
+                        }
+                    }
+                    ui.label("Pages");
+                    ui.label(info.page_count.to_string());
+                    ui.end_row();
+                });
+            });
+        }
+        ui.separator();
+
+        let width = ui.available_width() * *zoom;
+        let pixels = PdfPreview::render_width(width, ui.ctx().pixels_per_point());
+        if pdf.requested != Some((pdf.page, pixels)) {
+            let _ = tx.send(PdfRequest {
+                path: pdf.path.clone(),
+                page: pdf.page,
+                width: pixels,
+                reload: pdf.requested.is_none(),
+            });
+            pdf.requested = Some((pdf.page, pixels));
+            pdf.page_error = None;
+        }
+        egui::ScrollArea::both()
+            .auto_shrink([false; 2])
+            .show(ui, |ui| match (&pdf.page_error, &pdf.texture) {
+                // Only this page; paging on tries the next one
+                (Some(error), _) => {
+                    ui.weak(format!("Page {} could not be rendered: {}", pdf.page + 1, error));
+                }
+                (None, Some(texture)) => {
+                    let size = texture.size_vec2();
+                    let scale = width / size.x;
+                    ui.add(egui::Image::new(texture).fit_to_exact_size(size * scale));
+                }
+                (None, None) => {
+                    ui.spinner();
+                }
+            });
+    }
+
+    // Table/Source switch of CSV/TSV previews. Returns the view picked, if changed; the
+    // table can't be picked while the source has unsaved edits.
+    fn table_toggle(ui: &mut egui::Ui, showing_table: bool, can_switch: bool) -> Option<bool> {
+        let mut picked = None;
+        ui.horizontal(|ui| {
+            for (table, label) in [(true, "Table"), (false, "Source")] {
+                let enabled = table == showing_table || can_switch;
+                let label = egui::SelectableLabel::new(table == showing_table, label);
+                let resp = ui
+                    .add_enabled(enabled, label)
+                    .on_disabled_hover_text("Save or discard your edits first");
+                if resp.clicked() && table != showing_table {
+                    picked = Some(table);
+                }
+            }
+        });
+        picked
+    }
+
+    // Encoding and line ending of the open document, changing them converts on save
+    fn text_format_bar(ui: &mut egui::Ui, doc: &mut TextDocument) {
+        ui.horizontal(|ui| {
+            let detected = doc.saved_encoding();
+            egui::ComboBox::from_id_salt("preview_encoding")
+                .selected_text(doc.encoding.name())
+                .show_ui(ui, |ui| {
+                    if !ENCODING_CHOICES.contains(&detected) {
+                        ui.selectable_value(&mut doc.encoding, detected, detected.name());
+                    }
+                    for encoding in ENCODING_CHOICES {
+                        ui.selectable_value(&mut doc.encoding, encoding, encoding.name());
+                    }
+                });
+            if doc.supports_bom() {
+                ui.checkbox(&mut doc.bom, "BOM");
+            }
+
+            egui::ComboBox::from_id_salt("preview_line_ending")
+                .selected_text(doc.line_ending.label())
+                .width(60.0)
+                .show_ui(ui, |ui| {
+                    for ending in LineEnding::ALL {
+                        ui.selectable_value(&mut doc.line_ending, ending, ending.label());
+                    }
+                });
+            if doc.mixed_endings {
+                ui.weak("mixed").on_hover_text(format!(
+                    "The file mixes line endings, saving makes them all {}",
+                    doc.line_ending.label()
+                ));
+            }
+            if doc.lossy {
+                ui.colored_label(egui::Color32::from_rgb(243, 139, 168), "⚠ Invalid bytes")
+                    .on_hover_text("Not all of the file decoded, so it can't be saved");
+            }
+        });
+    }
+
+    fn save_current_file(&mut self) {
+        self.save_preview(false);
+    }
+
+    // Returns true once the edits are on disk. Unless `overwrite` is set, a file that
+    // another program changed since it was opened is left alone and the user asked.
+    fn save_preview(&mut self, overwrite: bool) -> bool {
+        let Some(PreviewData::Text(doc)) = &mut self.preview_data else {
+            return false;
+        };
+        if !overwrite && doc.changed_on_disk() {
+            self.save_conflict = true;
+            return false;
+        }
+        match doc.save() {
+            Ok(()) => true,
+            Err(e) => {
+                self.error_message = Some(format!("Failed to save: {}", e));
+                false
+            }
+        }
+    }
+
+    // Drops unsaved edits and shows `path` instead
+    fn discard_preview(&mut self, path: Option<PathBuf>) {
+        self.preview_data = None;
+        self.load_preview_for(path);
+    }
+
+    fn show_unsaved_prompts(&mut self, ctx: &egui::Context) {
+        let name = self
+            .preview_path
+            .as_ref()
+            .and_then(|p| p.file_name())
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        if self.save_conflict {
+            egui::Window::new("File Changed on Disk")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .show(ctx, |ui| {
+                    ui.label(format!(
+                        "\"{}\" was changed by another program since it was opened.",
+                        name
+                    ));
+                    // Reached from the unsaved changes prompt too, whose pending preview
+                    // goes ahead once the edits are saved or dropped
+                    ui.horizontal(|ui| {
+                        if ui.button("Overwrite").clicked() {
+                            self.save_conflict = false;
+                            if self.save_preview(true) {
+                                if let Some(next) = self.pending_preview.take() {
+                                    self.load_preview_for(next);
+                                }
+                            }
+                        }
+                        if ui.button("Reload").on_hover_text("Discard your edits").clicked() {
+                            self.save_conflict = false;
+                            let next = self.pending_preview.take();
+                            self.discard_preview(next.unwrap_or_else(|| self.preview_path.clone()));
+                        }
+                        if ui.button("Cancel").clicked() {
+                            self.save_conflict = false;
+                            if self.pending_preview.take().is_some() {
+                                self.restore_selection(self.preview_path.clone());
+                            }
+                        }
+                    });
+                });
+            return;
+        }
+
+        let Some(next) = self.pending_preview.clone() else {
+            return;
+        };
+        egui::Window::new("Unsaved Changes")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label(format!("Save changes to \"{}\"?", name));
+                ui.horizontal(|ui| {
+                    if ui.button("💾 Save").clicked() && self.save_preview(false) {
+                        self.pending_preview = None;
+                        self.load_preview_for(next.clone());
                     }
-                     "js" | "ts" | "jsx" | "tsx" | "html" | "css" | "json" | "yaml" | "yml" | "toml" => {
-                        ("📝", egui::Color32::from_rgb(137, 180, 250)) // Blue
+                    if ui.button("Discard").clicked() {
+                        self.pending_preview = None;
+                        self.discard_preview(next.clone());
                     }
-                     "py" | "rb" | "php" | "lua" | "pl" => {
-                        ("🐍", egui::Color32::from_rgb(249, 226, 175)) // Yellow
+                    if ui.button("Cancel").clicked() {
+                        // Keep editing, and point the selection back at the file
+                        self.pending_preview = None;
+                        self.restore_selection(self.preview_path.clone());
                     }
-                    
-                    // Images - Peach/Orange
-                    "png" | "jpg" | "jpeg" | "gif" | "bmp" | "webp" | "svg" | "ico" => {
-                        ("🖼", egui::Color32::from_rgb(250, 179, 135)) 
-                    } 
+                });
+            });
+    }
 
-                    // Documents - White/Gray
-                    "pdf" | "doc" | "docx" | "txt" | "md" | "odt" => {
-                        ("📄", egui::Color32::from_rgb(205, 214, 244))
-                    } 
+    // --- Operations ---
+
+    fn open_entry(&mut self, index: usize) {
+        if let Some(entry) = self.entries.get(index) {
+            match entry.file_type {
+                FileType::Directory => {
+                    self.navigate_to(entry.path.clone(), true);
+                }
+                FileType::File | FileType::Symlink => {
+                    let path = entry.path.clone();
+                    self.open_path(&path);
+                }
+                FileType::Unknown => {}
+            }
+        }
+    }
 
-                    // Archives - Pink/Red
-                    "zip" | "rar" | "7z" | "tar" | "gz" => {
-                        ("📦", egui::Color32::from_rgb(243, 139, 168)) 
-                    } 
+    fn open_path(&mut self, path: &Path) {
+        // The OS picks an app by extension, which text files without a known one lack
+        let text_without_app = file_category(path) == FileCategory::Other
+            && mime::detect(path).is_some_and(|d| d.kind == ContentKind::Text);
+        let result = match TEXT_EDITOR {
+            Some(editor) if text_without_app => open::with(path, editor),
+            _ => open::that(path),
+        };
+        if let Err(e) = result {
+            self.error_message = Some(format!("Failed to open: {}", e));
+        } else {
+            self.remember_recent(path.to_path_buf());
+        }
+    }
 
-                    // Executables / Scripts - Green
-                    "exe" | "msi" | "bat" | "sh" | "ps1" | "app" => {
-                        ("🚀", egui::Color32::from_rgb(166, 227, 161))
-                    } 
+    fn start_rename(&mut self) {
+        if let Some(idx) = self.selected_entry {
+            if let Some(entry) = self.entries.get(idx) {
+                self.renaming_index = Some(idx);
+                self.rename_buffer = entry.name.clone();
+            }
+        }
+    }
 
-                    // Media - Pink
-                    "mp3" | "wav" | "flac" | "mp4" | "mkv" | "avi" => {
-                        ("🎵", egui::Color32::from_rgb(245, 194, 231))
-                    } 
+    fn confirm_rename(&mut self) {
+        if let Some(idx) = self.renaming_index {
+            if let Some(entry) = self.entries.get(idx) {
+                if !self.rename_buffer.is_empty() && self.rename_buffer != entry.name {
+                    if let Err(e) = rename_entry(&entry.path, &self.rename_buffer) {
+                        self.error_message = Some(format!("Rename failed: {}", e));
+                    } else {
+                        self.refresh();
+                    }
+                }
+            }
+        }
+        self.renaming_index = None;
+    }
 
-                    _ => ("📄", egui::Color32::from_rgb(166, 173, 200)), // Default
+    fn delete_selected(&mut self) {
+        if let Some(idx) = self.selected_entry {
+            if let Some(entry) = self.entries.get(idx) {
+                if let Err(e) = delete_entry(&entry.path) {
+                    self.error_message = Some(format!("Delete failed: {}", e));
+                } else {
+                    self.select_entry(None);
+                    self.refresh();
                 }
             }
+        }
+    }
+
+    // Files without a telling extension get their category from a peek at their content.
+    // Only rows in view are read, and each file once per visit to the folder.
+    fn sniff_entries(&mut self, indices: &[usize]) {
+        for &i in indices {
+            let Some(entry) = self.entries.get_mut(i) else {
+                continue;
+            };
+            if entry.file_type != FileType::File || entry.category != FileCategory::Other {
+                continue;
+            }
+            entry.category = *self
+                .sniffed
+                .entry(entry.path.clone())
+                .or_insert_with(|| mime::sniff_category(&entry.path));
+        }
+    }
+
+    fn get_icon_for_entry(&self, entry: &FileEntry) -> (&'static str, egui::Color32) {
+        match entry.file_type {
+            FileType::Directory => ("📁", egui::Color32::from_rgb(249, 226, 175)), // Yellow (Peach-ish)
+            FileType::Symlink => ("🔗", egui::Color32::from_rgb(148, 226, 213)),   // Teal
+            FileType::File => match entry.category {
+                // Code / Config - Blue/Lavender
+                FileCategory::Code => ("🦀", egui::Color32::from_rgb(203, 166, 247)), // Mauve for low level
+                FileCategory::WebConfig => ("📝", egui::Color32::from_rgb(137, 180, 250)), // Blue
+                FileCategory::Script => ("🐍", egui::Color32::from_rgb(249, 226, 175)), // Yellow
+
+                // Images - Peach/Orange
+                FileCategory::Image => ("🖼", egui::Color32::from_rgb(250, 179, 135)),
+
+                // Documents - White/Gray
+                FileCategory::Document => ("📄", egui::Color32::from_rgb(205, 214, 244)),
+
+                // Archives - Pink/Red
+                FileCategory::Archive => ("📦", egui::Color32::from_rgb(243, 139, 168)),
+
+                // Executables / Scripts - Green
+                FileCategory::Executable => ("🚀", egui::Color32::from_rgb(166, 227, 161)),
+
+                // Media - Pink
+                FileCategory::Media => ("🎵", egui::Color32::from_rgb(245, 194, 231)),
+
+                FileCategory::Other => ("📄", egui::Color32::from_rgb(166, 173, 200)), // Default
+            },
             FileType::Unknown => ("?", egui::Color32::from_rgb(243, 139, 168)), // Red
         }
     }
@@ -629,26 +2119,232 @@ impl ExplorerApp {
         }
     }
 
+    fn show_content_results(&mut self, ui: &mut egui::Ui) {
+        let Some(results) = &self.content_results else {
+            return;
+        };
+
+        if results.is_empty() {
+            ui.centered_and_justified(|ui| {
+                if self.is_searching {
+                    ui.spinner();
+                } else {
+                    ui.label("No matches found");
+                }
+            });
+            return;
+        }
+
+        let highlight = ui.visuals().selection.bg_fill;
+        let text_color = ui.visuals().text_color();
+        let meta_color = egui::Color32::from_rgb(108, 112, 134);
+        let mut match_to_select = None;
+        let mut file_to_open = None;
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for (i, m) in results.iter().enumerate() {
+                let rel_path = m.path.strip_prefix(&self.current_path).unwrap_or(&m.path);
+
+                ui.horizontal(|ui| {
+                    ui.colored_label(
+                        meta_color,
+                        format!("{}:{}", rel_path.to_string_lossy(), m.line_number),
+                    );
+
+                    let mut job = egui::text::LayoutJob::default();
+                    let font = egui::FontId::monospace(12.0);
+                    let plain = egui::TextFormat::simple(font.clone(), text_color);
+                    let hit = egui::TextFormat {
+                        background: highlight,
+                        ..plain.clone()
+                    };
+                    job.append(&m.snippet[..m.match_range.start], 0.0, plain.clone());
+                    job.append(&m.snippet[m.match_range.clone()], 0.0, hit);
+                    job.append(&m.snippet[m.match_range.end..], 0.0, plain);
+
+                    let resp = ui.selectable_label(self.selected_match == Some(i), job);
+                    if resp.clicked() {
+                        match_to_select = Some(i);
+                    }
+                    if resp.double_clicked() {
+                        file_to_open = Some(m.path.clone());
+                    }
+                });
+            }
+        });
+
+        if let Some(i) = match_to_select {
+            self.select_match(i);
+        }
+        if let Some(path) = file_to_open {
+            self.open_path(&path);
+        }
+    }
+
     fn perform_search(&mut self) {
-         if self.search_query.is_empty() {
+         let filter_active = self.search_filter.is_active();
+         if self.search_query.is_empty() && (!filter_active || self.content_search) {
              self.refresh();
              return;
          }
 
-         if self.recursive_search {
-             self.is_loading = true;
-             let tx = self.load_res_tx.clone();
+         if self.content_search {
+             let matcher = match build_content_matcher(&self.search_query, self.content_regex) {
+                 Ok(m) => m,
+                 Err(e) => {
+                     self.error_message = Some(format!("Invalid pattern: {}", e));
+                     return;
+                 }
+             };
+             let (id, cancel) = self.start_search();
+             self.content_results = Some(Vec::new());
+             self.selected_match = None;
+             let tx = self.search_res_tx.clone();
              let root = self.current_path.clone();
-             let query = self.search_query.clone();
-             
+             let options = self.walk_options.clone();
+
              thread::spawn(move || {
-                 let results = search_directory_recursive(&root, &query);
-                 let _ = tx.send(Ok(results));
+                 let result = search_file_contents(&root, &matcher, &options, &cancel, |batch| {
+                     let _ = tx.send(SearchUpdate::Matches(id, batch));
+                 });
+                 if let Err(e) = result {
+                     let _ = tx.send(SearchUpdate::Failed(id, e));
+                 }
+                 let _ = tx.send(SearchUpdate::Done(id));
              });
+             return;
+         }
+
+         if self.index_serves_search() {
+             if let Some(index) = self.index.clone() {
+                 let (id, _) = self.start_search();
+                 self.search_ranked = true;
+                 self.select_entry(None);
+                 self.entries.clear();
+                 self.showing_search_results = true;
+                 self.is_loading = false;
+                 let tx = self.search_res_tx.clone();
+                 let root = self.current_path.clone();
+                 let query = self.search_query.clone();
+                 let show_hidden = self.search_filter.include_hidden || self.show_hidden;
+                 let max_depth = self.walk_options.max_depth;
+
+                 thread::spawn(move || {
+                     let mut results = index.search(&root, &query, max_depth, INDEX_RESULT_LIMIT);
+                     if !show_hidden {
+                         results.retain(|e| !e.is_hidden);
+                     }
+                     let _ = tx.send(SearchUpdate::Entries(id, results));
+                     let _ = tx.send(SearchUpdate::Done(id));
+                 });
+                 return;
+             }
+         }
+
+         // Metadata filters only apply to the recursive walk
+         if self.recursive_search || filter_active {
+             self.search_recursive(self.search_query.clone(), self.search_filter.clone());
          }
          // If local, the UI loop filters automatically.
     }
 
+    // Walks the current folder for names containing `query`, streaming results into the listing
+    fn search_recursive(&mut self, query: String, mut filter: SearchFilter) {
+        let (id, cancel) = self.start_search();
+        self.select_entry(None);
+        self.entries.clear();
+        self.showing_search_results = true;
+        self.is_loading = false;
+        let tx = self.search_res_tx.clone();
+        let root = self.current_path.clone();
+        // Showing hidden files in the listing still shows them in search results
+        filter.include_hidden |= self.show_hidden;
+        let options = self.walk_options.clone();
+
+        thread::spawn(move || {
+            let result =
+                search_directory_recursive(&root, &query, &filter, &options, &cancel, |batch| {
+                    let _ = tx.send(SearchUpdate::Entries(id, batch));
+                });
+            if let Err(e) = result {
+                let _ = tx.send(SearchUpdate::Failed(id, e));
+            }
+            let _ = tx.send(SearchUpdate::Done(id));
+        });
+    }
+
+    // Plain recursive name searches are answered from the index when it covers this folder
+    fn index_serves_search(&self) -> bool {
+        self.recursive_search
+            && !self.content_search
+            && !self.search_filter.is_active()
+            && self
+                .index
+                .as_ref()
+                .is_some_and(|index| index.covers(&self.current_path))
+    }
+
+    /// Cancels whatever search is running and hands out the id and cancel flag
+    /// for a new one. Updates tagged with an older id are dropped in `update()`.
+    fn start_search(&mut self) -> (u64, Arc<AtomicBool>) {
+        self.cancel_search();
+        self.is_searching = true;
+        self.search_ranked = false;
+        self.error_message = None;
+        let cancel = Arc::new(AtomicBool::new(false));
+        self.search_cancel = Some(cancel.clone());
+        (self.search_id, cancel)
+    }
+
+    fn cancel_hex_search(&mut self) {
+        if let Some(cancel) = self.hex_search.take() {
+            cancel.store(true, Ordering::Relaxed);
+        }
+        self.hex_search_id += 1;
+    }
+
+    // Also retires the search id, so batches the worker sent before it saw the flag
+    // are dropped rather than mixed into whatever is shown next
+    fn cancel_search(&mut self) {
+        if let Some(cancel) = self.search_cancel.take() {
+            cancel.store(true, Ordering::Relaxed);
+        }
+        self.search_id += 1;
+        self.is_searching = false;
+    }
+
+    fn handle_search_update(&mut self, update: SearchUpdate) {
+        match update {
+            SearchUpdate::Entries(id, batch) if id == self.search_id => {
+                // Appended unsorted so indices (and the selection) stay stable while streaming
+                self.entries.extend(batch);
+            }
+            SearchUpdate::Matches(id, batch) if id == self.search_id => {
+                if let Some(results) = &mut self.content_results {
+                    results.extend(batch);
+                }
+            }
+            SearchUpdate::Failed(id, e) if id == self.search_id => {
+                self.error_message = Some(format!("Search failed: {}", e));
+            }
+            SearchUpdate::Done(id) if id == self.search_id => {
+                self.search_cancel = None;
+                self.is_searching = false;
+                self.sort_search_results();
+            }
+            _ => {} // Stale update from a superseded search
+        }
+    }
+
+    // Results stream in unsorted; once complete (or stopped) they take the usual order
+    fn sort_search_results(&mut self) {
+        let selected = self.selected_path();
+        if !self.search_ranked {
+            self.sort_entries();
+        }
+        self.restore_selection(selected);
+    }
+
     fn compress_selected(&mut self) {
         if let Some(idx) = self.selected_entry {
             if let Some(entry) = self.entries.get(idx) {
@@ -715,19 +2411,107 @@ impl ExplorerApp {
 impl eframe::App for ExplorerApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         // --- Event Handling ---
-        if let Ok(result) = self.load_res_rx.try_recv() {
-            self.is_loading = false;
-            match result {
-                Ok(mut entries) => {
-                    if !self.show_hidden {
-                        entries.retain(|e| !e.is_hidden);
+        while let Ok(update) = self.load_res_rx.try_recv() {
+            self.handle_load_update(update);
+        }
+        while let Ok(event) = self.watch_rx.try_recv() {
+            match event {
+                // Access events (opening/reading files) don't change the listing
+                Ok(event) if !matches!(event.kind, notify::EventKind::Access(_)) => {
+                    self.pending_fs_changes.extend(event.paths);
+                }
+                Ok(_) => {}
+                Err(e) => log::warn!("File watcher error: {}", e),
+            }
+        }
+        self.apply_fs_changes();
+        while let Ok((path, size)) = self.size_res_rx.try_recv() {
+            self.handle_folder_size(path, size);
+        }
+        if let Some(PreviewData::Paged(paged)) = &mut self.preview_data {
+            if self.preview_follow {
+                if self.follow_checked.elapsed() >= FOLLOW_INTERVAL {
+                    self.follow_checked = Instant::now();
+                    if let Err(e) = paged.reload() {
+                        self.error_message = Some(format!("Cannot follow file: {}", e));
+                        self.preview_follow = false;
                     }
-                    self.entries = entries;
-                    self.sort_entries();
                 }
-                Err(e) => self.error_message = Some(e),
+                ctx.request_repaint_after(FOLLOW_INTERVAL);
+            }
+            // Index big files a slice per frame so the UI stays responsive
+            if !paged.is_indexed() {
+                paged.index_more(PAGER_INDEX_BUDGET);
+                ctx.request_repaint();
+            }
+        }
+        while let Ok(update) = self.pdf_rx.try_recv() {
+            if let Some(PreviewData::Pdf(pdf)) = &mut self.preview_data {
+                pdf.apply(update, ctx);
+            }
+        }
+        if let Some(PreviewData::Table(table)) = &mut self.preview_data {
+            if !table.is_loaded() {
+                if let Err(e) = table.load_more(TABLE_LOAD_BUDGET) {
+                    self.error_message = Some(format!("Cannot read table: {}", e));
+                }
+                ctx.request_repaint();
+            }
+        }
+        while let Ok(drives) = self.drives_rx.try_recv() {
+            self.drives = drives;
+        }
+        while let Ok((id, node)) = self.usage_rx.try_recv() {
+            if id == self.usage_id {
+                self.usage = Some(node);
+                self.usage_scanning = None;
+            }
+        }
+        if self.view_mode == ViewMode::Usage {
+            self.ensure_usage_scan();
+        }
+        if self.usage_scanning.is_some() {
+            ctx.request_repaint_after(std::time::Duration::from_millis(100));
+        }
+        while let Ok((id, paths)) = self.quick_open_rx.try_recv() {
+            if id == self.quick_open_id && self.quick_open {
+                self.quick_open_scanning = false;
+                self.quick_open_candidates = paths;
+                self.update_quick_open_results();
+            }
+        }
+        while let Ok((generation, index)) = self.index_rx.try_recv() {
+            if self.index_enabled && generation == self.index_generation {
+                self.index = Some(index);
+            }
+        }
+        while let Ok((id, found)) = self.hex_search_rx.try_recv() {
+            if id != self.hex_search_id {
+                continue; // Cancelled, or the preview moved on
+            }
+            self.hex_search = None;
+            match found {
+                Ok(Some(offset)) => {
+                    self.hex_cursor = offset;
+                    self.hex_jump = true;
+                }
+                Ok(None) => self.hex_status = Some("Not found".to_string()),
+                Err(e) => self.hex_status = Some(e),
             }
         }
+        while let Ok(update) = self.search_res_rx.try_recv() {
+            self.handle_search_update(update);
+        }
+        if self.is_searching {
+            // Results arrive from a worker thread, keep polling while it runs
+            ctx.request_repaint_after(std::time::Duration::from_millis(100));
+        }
+
+        // Quick open works from anywhere, even with the editor or a text box focused;
+        // the key is consumed so that widget doesn't also see it
+        if ctx.input_mut(|i| i.consume_key(egui::Modifiers::COMMAND, egui::Key::P)) {
+            self.open_quick_open();
+        }
 
         // Global Shortcuts
         if !ctx.wants_keyboard_input() {
@@ -820,6 +2604,7 @@ impl eframe::App for ExplorerApp {
                 let view_mode_icon = match self.view_mode {
                     ViewMode::List => "☰",
                     ViewMode::Grid => "⣿",
+                    ViewMode::Usage => "📊",
                 };
                 if ui
                     .button(view_mode_icon)
@@ -828,7 +2613,11 @@ impl eframe::App for ExplorerApp {
                 {
                     self.view_mode = match self.view_mode {
                         ViewMode::List => ViewMode::Grid,
-                        ViewMode::Grid => ViewMode::List,
+                        ViewMode::Grid => ViewMode::Usage,
+                        ViewMode::Usage => {
+                            self.cancel_usage_scan();
+                            ViewMode::List
+                        }
                     };
                     self.save_state();
                 }
@@ -842,6 +2631,14 @@ impl eframe::App for ExplorerApp {
                     self.refresh();
                     self.save_state();
                 }
+                if ui
+                    .checkbox(&mut self.auto_folder_sizes, "Folder Sizes")
+                    .on_hover_text("Calculate the size of every folder in the listing")
+                    .changed()
+                {
+                    self.request_folder_sizes();
+                    self.save_state();
+                }
 
                 ui.separator();
                 if ui.button("💻").on_hover_text("Open in Terminal").clicked() {
@@ -910,6 +2707,13 @@ impl eframe::App for ExplorerApp {
                             self.path_edit_mode = false;
                         }
                     } else {
+                        if let Some(saved) =
+                            self.active_saved_search.and_then(|i| self.saved_searches.get(i))
+                        {
+                            ui.label(egui::RichText::new(format!("🔍 {}", saved.name)).strong());
+                            ui.label("in");
+                        }
+
                         // Breadcrumbs
                         let mut path_to_navigate = None;
                         egui::ScrollArea::horizontal()
@@ -950,6 +2754,39 @@ impl eframe::App for ExplorerApp {
                 ui.add_space(10.0);
                 ui.label("🔍");
                 ui.checkbox(&mut self.recursive_search, "Recursive");
+                let filter_label = if self.search_filter.is_active() {
+                    egui::RichText::new("⚙ Filters").strong()
+                } else {
+                    egui::RichText::new("⚙ Filters")
+                };
+                if ui
+                    .button(filter_label)
+                    .on_hover_text("Size, date and type filters for recursive search")
+                    .clicked()
+                {
+                    self.filter_window_open = !self.filter_window_open;
+                }
+                ui.checkbox(&mut self.content_search, "Contents")
+                    .on_hover_text("Search inside files under the current folder");
+                if self.content_search {
+                    ui.checkbox(&mut self.content_regex, ".*")
+                        .on_hover_text("Treat the query as a regular expression");
+                }
+                if ui
+                    .add_enabled(self.can_save_search(), egui::Button::new("💾"))
+                    .on_hover_text("Save Search")
+                    .clicked()
+                {
+                    self.save_search_popup_open = true;
+                }
+                if self.is_searching
+                    && (ui.button("✖").on_hover_text("Cancel Search").clicked()
+                        || ctx.input(|i| i.key_pressed(egui::Key::Escape)))
+                {
+                    // What was found so far stays listed
+                    self.cancel_search();
+                    self.sort_search_results();
+                }
                 let search_resp = ui.add_sized(
                     ui.available_size(),
                     egui::TextEdit::singleline(&mut self.search_query).hint_text("Search..."),
@@ -962,6 +2799,12 @@ impl eframe::App for ExplorerApp {
                 
                 if search_resp.lost_focus() && ctx.input(|i| i.key_pressed(egui::Key::Enter)) {
                     self.perform_search();
+                } else if search_resp.changed()
+                    && !self.search_query.is_empty()
+                    && self.index_serves_search()
+                {
+                    // The index is cheap enough to query on every keystroke
+                    self.perform_search();
                 }
             });
             ui.add_space(4.0);
@@ -974,19 +2817,207 @@ impl eframe::App for ExplorerApp {
                 .resizable(false)
                 .show(ctx, |ui| {
                     ui.horizontal(|ui| {
-                        ui.radio_value(&mut self.create_folder, true, "Folder");
-                        ui.radio_value(&mut self.create_folder, false, "File");
+                        ui.radio_value(&mut self.create_folder, true, "Folder");
+                        ui.radio_value(&mut self.create_folder, false, "File");
+                    });
+                    ui.text_edit_singleline(&mut self.new_item_name);
+                    ui.horizontal(|ui| {
+                        if ui.button("Create").clicked() {
+                            self.create_new_item();
+                        }
+                        if ui.button("Cancel").clicked() {
+                            self.creation_popup_open = false;
+                        }
+                    });
+                });
+        }
+
+        // --- Save Search Popup ---
+        if self.save_search_popup_open {
+            egui::Window::new("Save Search")
+                .collapsible(false)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    ui.label(format!(
+                        "\"{}\" in {}",
+                        self.search_query,
+                        self.current_path.to_string_lossy()
+                    ));
+                    ui.text_edit_singleline(&mut self.save_search_name);
+                    ui.horizontal(|ui| {
+                        if ui.button("Save").clicked() {
+                            self.save_current_search();
+                        }
+                        if ui.button("Cancel").clicked() {
+                            self.save_search_popup_open = false;
+                        }
+                    });
+                });
+        }
+
+        // --- Quick Open ---
+        if self.quick_open {
+            self.show_quick_open(ctx);
+        }
+
+        // --- Disk Usage Removal ---
+        self.show_usage_confirm(ctx);
+
+        // --- Unsaved Edits ---
+        self.show_unsaved_prompts(ctx);
+
+        // --- Search Filters ---
+        if self.filter_window_open {
+            let mut open = true;
+            let mut run_search = false;
+            let mut save_options = false;
+            let mut toggle_index = false;
+            egui::Window::new("Search Filters")
+                .open(&mut open)
+                .collapsible(false)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    let filter = &mut self.search_filter;
+
+                    egui::Grid::new("filter_grid")
+                        .num_columns(2)
+                        .spacing([10.0, 6.0])
+                        .show(ui, |ui| {
+                            ui.label("Min size");
+                            size_bound_ui(ui, &mut filter.min_size);
+                            ui.end_row();
+                            ui.label("Max size");
+                            size_bound_ui(ui, &mut filter.max_size);
+                            ui.end_row();
+
+                            ui.label("Modified after");
+                            date_bound_ui(ui, "modified_after", &mut filter.modified_after, false);
+                            ui.end_row();
+                            ui.label("Modified before");
+                            date_bound_ui(ui, "modified_before", &mut filter.modified_before, true);
+                            ui.end_row();
+                            ui.label("Created after");
+                            date_bound_ui(ui, "created_after", &mut filter.created_after, false);
+                            ui.end_row();
+                            ui.label("Created before");
+                            date_bound_ui(ui, "created_before", &mut filter.created_before, true);
+                            ui.end_row();
+                        });
+
+                    ui.separator();
+                    ui.label("Types");
+                    ui.horizontal_wrapped(|ui| {
+                        for category in FileCategory::ALL {
+                            let mut checked = filter.categories.contains(&category);
+                            if ui.checkbox(&mut checked, category.label()).changed() {
+                                if checked {
+                                    filter.categories.push(category);
+                                } else {
+                                    filter.categories.retain(|c| *c != category);
+                                }
+                            }
+                        }
+                    });
+
+                    ui.separator();
+                    ui.checkbox(&mut filter.include_hidden, "Include hidden files");
+
+                    ui.separator();
+                    let options = &mut self.walk_options;
+                    let mut options_changed = false;
+                    options_changed |= ui
+                        .checkbox(&mut options.respect_ignore_files, "Respect .gitignore / .ignore")
+                        .changed();
+                    options_changed |= ui
+                        .checkbox(&mut options.follow_symlinks, "Follow symlinks")
+                        .changed();
+                    ui.horizontal(|ui| {
+                        let mut limited = options.max_depth.is_some();
+                        if ui.checkbox(&mut limited, "Max depth").changed() {
+                            options.max_depth = if limited { Some(3) } else { None };
+                            options_changed = true;
+                        }
+                        if let Some(depth) = &mut options.max_depth {
+                            options_changed |= ui
+                                .add(egui::DragValue::new(depth).range(1..=64))
+                                .changed();
+                        }
                     });
-                    ui.text_edit_singleline(&mut self.new_item_name);
+                    ui.label("Exclude (one pattern per line)");
+                    if ui
+                        .add(
+                            egui::TextEdit::multiline(&mut self.excludes_buffer)
+                                .desired_rows(3)
+                                .hint_text("node_modules\n*.log"),
+                        )
+                        .changed()
+                    {
+                        options.excludes = self
+                            .excludes_buffer
+                            .lines()
+                            .map(|l| l.trim().to_string())
+                            .filter(|l| !l.is_empty())
+                            .collect();
+                        options_changed = true;
+                    }
+                    if options_changed {
+                        save_options = true;
+                    }
+
+                    ui.separator();
+                    if ui
+                        .checkbox(&mut self.index_enabled, "Index favorites for instant search")
+                        .changed()
+                    {
+                        toggle_index = true;
+                    }
+                    if self.index_enabled {
+                        match &self.index {
+                            Some(index) => {
+                                let updated = Local
+                                    .timestamp_opt(index.updated, 0)
+                                    .single()
+                                    .map(|dt| dt.format("%H:%M").to_string())
+                                    .unwrap_or_default();
+                                ui.label(format!(
+                                    "{} entries indexed, updated {}",
+                                    index.len(),
+                                    updated
+                                ));
+                            }
+                            None => {
+                                ui.horizontal(|ui| {
+                                    ui.spinner();
+                                    ui.label("Building index...");
+                                });
+                            }
+                        }
+                    }
+
                     ui.horizontal(|ui| {
-                        if ui.button("Create").clicked() {
-                            self.create_new_item();
+                        if ui.button("Search").clicked() {
+                            run_search = true;
                         }
-                        if ui.button("Cancel").clicked() {
-                            self.creation_popup_open = false;
+                        if ui.button("Clear").clicked() {
+                            *filter = SearchFilter::default();
                         }
                     });
                 });
+            self.filter_window_open = open;
+            // The index is built with the walk options, so new options mean a new index
+            if toggle_index || (save_options && self.index_enabled) {
+                if self.index_enabled {
+                    self.start_indexer();
+                } else {
+                    self.stop_indexer();
+                }
+            }
+            if save_options || toggle_index {
+                self.save_state();
+            }
+            if run_search {
+                self.perform_search();
+            }
         }
 
         // --- Password Modal ---
@@ -1029,7 +3060,16 @@ impl eframe::App for ExplorerApp {
         // --- Bottom Status Bar ---
         egui::TopBottomPanel::bottom("bottom_panel").show(ctx, |ui| {
             ui.horizontal(|ui| {
-                ui.label(format!("{} items", self.entries.len()));
+                let count = match &self.content_results {
+                    Some(results) => format!("{} matches", results.len()),
+                    None => format!("{} items", self.entries.len()),
+                };
+                if self.is_searching {
+                    ui.spinner();
+                    ui.label(format!("Searching... {}", count));
+                } else {
+                    ui.label(count);
+                }
                 if let Some(err) = &self.error_message {
                     ui.separator();
                     ui.colored_label(egui::Color32::RED, format!("⚠ {}", err));
@@ -1078,6 +3118,40 @@ impl eframe::App for ExplorerApp {
                     self.navigate_to(path, true);
                 }
 
+                if !self.saved_searches.is_empty() {
+                    ui.separator();
+                    ui.heading("Saved Searches");
+                    ui.separator();
+                    let mut search_to_open = None;
+                    let mut search_to_remove = None;
+                    egui::ScrollArea::vertical()
+                        .id_salt("saved_search_scroll")
+                        .max_height(150.0)
+                        .show(ui, |ui| {
+                            for (i, saved) in self.saved_searches.iter().enumerate() {
+                                let is_active = self.active_saved_search == Some(i);
+                                let resp = ui
+                                    .selectable_label(is_active, format!("🔍 {}", saved.name))
+                                    .on_hover_text(saved.root.to_string_lossy());
+                                if resp.clicked() {
+                                    search_to_open = Some(i);
+                                }
+                                resp.context_menu(|ui| {
+                                    if ui.button("Remove").clicked() {
+                                        search_to_remove = Some(i);
+                                        ui.close_menu();
+                                    }
+                                });
+                            }
+                        });
+                    if let Some(i) = search_to_open {
+                        self.open_saved_search(i);
+                    }
+                    if let Some(i) = search_to_remove {
+                        self.remove_saved_search(i);
+                    }
+                }
+
                 ui.separator();
                 ui.heading("Drives");
                 ui.separator();
@@ -1086,12 +3160,56 @@ impl eframe::App for ExplorerApp {
                 egui::ScrollArea::vertical()
                     .id_salt("drive_scroll")
                     .show(ui, |ui| {
+                        // Several mounts can contain current_path, the deepest one is "current"
+                        let active = self
+                            .drives
+                            .iter()
+                            .filter(|d| self.current_path.starts_with(&d.mount_point))
+                            .max_by_key(|d| d.mount_point.components().count())
+                            .map(|d| d.mount_point.clone());
                         for drive in &self.drives {
-                            let label = drive.to_string_lossy().to_string();
-                            let is_active = self.current_path.starts_with(drive);
-                            if ui.selectable_label(is_active, &label).clicked() {
-                                drive_to_open = Some(drive.clone());
+                            let mount = drive.mount_point.to_string_lossy().to_string();
+                            let icon = if drive.is_removable { "🔌" } else { "💽" };
+                            let label = if drive.name.is_empty() || drive.name == mount {
+                                format!("{} {}", icon, mount)
+                            } else {
+                                format!("{} {} ({})", icon, drive.name, mount)
+                            };
+                            let is_active = active.as_ref() == Some(&drive.mount_point);
+                            let resp = ui.selectable_label(is_active, label).on_hover_text(format!(
+                                "{}{}{}",
+                                drive.file_system,
+                                if drive.is_removable { ", removable" } else { "" },
+                                if drive.is_read_only { ", read-only" } else { "" },
+                            ));
+                            if resp.clicked() {
+                                drive_to_open = Some(drive.mount_point.clone());
                             }
+
+                            let used = drive.total_space.saturating_sub(drive.available_space);
+                            let fraction = if drive.total_space > 0 {
+                                used as f32 / drive.total_space as f32
+                            } else {
+                                0.0
+                            };
+                            // Nearly full drives stand out in red
+                            let bar_color = if fraction > 0.9 {
+                                egui::Color32::from_rgb(243, 139, 168)
+                            } else {
+                                egui::Color32::from_rgb(137, 180, 250)
+                            };
+                            ui.add(
+                                egui::ProgressBar::new(fraction)
+                                    .desired_height(6.0)
+                                    .fill(bar_color),
+                            );
+                            ui.small(format!(
+                                "{} free of {} · {}",
+                                format_size(drive.available_space, DECIMAL),
+                                format_size(drive.total_space, DECIMAL),
+                                drive.file_system
+                            ));
+                            ui.add_space(4.0);
                         }
                     });
                 if let Some(d) = drive_to_open {
@@ -1107,31 +3225,391 @@ impl eframe::App for ExplorerApp {
                 .show(ctx, |ui| {
                     ui.horizontal(|ui| {
                         ui.heading("Preview");
-                        if let Some(PreviewData::Text(_)) = &self.preview_data {
+                        if let Some(mime) = self.preview_mime {
+                            ui.weak(mime);
+                        }
+                        let dirty = self.preview_is_dirty();
+                        if let Some(PreviewData::Text(doc)) = &self.preview_data {
+                            let can_save = dirty && !doc.lossy;
                             ui.with_layout(
                                 egui::Layout::right_to_left(egui::Align::Center),
                                 |ui| {
-                                    if ui.button("💾 Save").clicked() {
+                                    let save = egui::Button::new("💾 Save");
+                                    if ui.add_enabled(can_save, save).clicked() {
                                         self.save_current_file();
                                     }
+                                    if dirty {
+                                        ui.colored_label(
+                                            egui::Color32::from_rgb(249, 226, 175),
+                                            "● Modified",
+                                        );
+                                    }
                                 },
                             );
                         }
                     });
                     ui.separator();
 
+                    let mut open_link = None; // Local link clicked in rendered Markdown
+                    let mut switch_table_view = None; // CSV/TSV view picked by the user
                     match &mut self.preview_data {
-                        Some(PreviewData::Text(content)) => {
-                            egui::ScrollArea::vertical().show(ui, |ui| {
-                                ui.add_sized(
-                                    ui.available_size(),
-                                    egui::TextEdit::multiline(content)
-                                        .code_editor()
-                                        .font(egui::TextStyle::Monospace),
+                        Some(PreviewData::Text(doc)) => {
+                            let path = self.preview_path.clone().unwrap_or_default();
+                            let is_markdown = markdown::is_markdown(&path);
+                            if table::is_table(&path) {
+                                switch_table_view = Self::table_toggle(ui, false, !doc.is_dirty());
+                            }
+                            if is_markdown {
+                                ui.horizontal(|ui| {
+                                    let rendered = &mut self.markdown_rendered;
+                                    ui.selectable_value(rendered, true, "Rendered");
+                                    ui.selectable_value(rendered, false, "Source");
+                                });
+                            }
+                            Self::text_format_bar(ui, doc);
+                            ui.separator();
+                            // Search hits are shown in the source
+                            if is_markdown && self.preview_scroll_line.is_some() {
+                                self.markdown_rendered = false;
+                            }
+                            if is_markdown && self.markdown_rendered {
+                                let base_dir = path.parent().unwrap_or(Path::new(""));
+                                open_link = egui::ScrollArea::vertical()
+                                    .auto_shrink([false; 2])
+                                    .show(ui, |ui| self.markdown.show(ui, &doc.content, base_dir))
+                                    .inner;
+                            } else {
+                                let content = &mut doc.content;
+                                let theme = self.theme.syntax_theme();
+                                let highlighter = &self.highlighter;
+                                let gutter = &mut self.line_gutter;
+                                let mut scroll = egui::ScrollArea::both();
+                                if let Some(line) = self.preview_scroll_line.take() {
+                                    // Leave a few lines of context above the hit
+                                    let row_height =
+                                        ui.text_style_height(&egui::TextStyle::Monospace);
+                                    scroll = scroll.vertical_scroll_offset(
+                                        line.saturating_sub(4) as f32 * row_height,
+                                    );
+                                }
+                                scroll.show(ui, |ui| {
+                                    let font_id = egui::TextStyle::Monospace.resolve(ui.style());
+                                    // A trailing newline still starts a (blank) last line
+                                    let line_count = content.lines().count().max(1)
+                                        + usize::from(content.ends_with('\n'));
+                                    if gutter.0 != line_count {
+                                        *gutter = (
+                                            line_count,
+                                            (1..=line_count)
+                                                .map(|n| n.to_string())
+                                                .collect::<Vec<_>>()
+                                                .join("\n"),
+                                        );
+                                    }
+
+                                    ui.horizontal_top(|ui| {
+                                        ui.vertical(|ui| {
+                                            ui.add_space(2.0); // Matches the editor's inner margin
+                                            ui.add(
+                                                egui::Label::new(
+                                                    egui::RichText::new(gutter.1.as_str())
+                                                        .font(font_id.clone())
+                                                        .color(ui.visuals().weak_text_color()),
+                                                )
+                                                .selectable(false),
+                                            );
+                                        });
+
+                                        let mut layouter = |ui: &egui::Ui, text: &str, _: f32| {
+                                            let font_id = font_id.clone();
+                                            let job =
+                                                highlighter.layout_job(text, &path, theme, font_id);
+                                            ui.fonts(|f| f.layout_job(job))
+                                        };
+                                        ui.add_sized(
+                                            ui.available_size(),
+                                            egui::TextEdit::multiline(content)
+                                                .code_editor()
+                                                .font(egui::TextStyle::Monospace)
+                                                .layouter(&mut layouter),
+                                        );
+                                    });
+                                });
+                            }
+                        }
+                        Some(PreviewData::Paged(paged)) => {
+                            let mut jump = self.preview_scroll_line.take();
+                            if self.preview_path.as_deref().is_some_and(table::is_table) {
+                                switch_table_view = Self::table_toggle(ui, false, true);
+                            }
+                            ui.horizontal(|ui| {
+                                ui.label("Line");
+                                let resp = ui.add(
+                                    egui::TextEdit::singleline(&mut self.preview_goto)
+                                        .desired_width(70.0),
+                                );
+                                let entered = resp.lost_focus()
+                                    && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                                if ui.button("Go").clicked() || entered {
+                                    jump = self.preview_goto.trim().parse().ok();
+                                }
+                                ui.checkbox(&mut self.preview_follow, "Follow")
+                                    .on_hover_text("Show lines as they are appended (tail -f)");
+                            });
+                            ui.horizontal(|ui| {
+                                ui.weak(format!(
+                                    "{} lines · {}",
+                                    paged.line_count(),
+                                    format_size(paged.len() as u64, DECIMAL)
+                                ));
+                                if !paged.is_indexed() {
+                                    ui.add(
+                                        egui::ProgressBar::new(paged.progress())
+                                            .desired_width(80.0)
+                                            .show_percentage(),
+                                    );
+                                }
+                            });
+                            ui.separator();
+
+                            let total = paged.line_count();
+                            let digits = total.max(1).to_string().len();
+                            let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+                            let pitch = row_height + ui.spacing().item_spacing.y;
+                            let mut scroll = egui::ScrollArea::both().auto_shrink([false; 2]);
+                            if self.preview_follow {
+                                scroll = scroll.vertical_scroll_offset(total as f32 * pitch);
+                            } else if let Some(line) = jump {
+                                scroll = scroll
+                                    .vertical_scroll_offset(line.saturating_sub(4) as f32 * pitch);
+                            }
+                            scroll.show_rows(ui, row_height, total, |ui, rows| {
+                                let lines = paged.lines(rows.start, rows.len());
+                                for (i, line) in rows.zip(lines) {
+                                    ui.horizontal(|ui| {
+                                        ui.label(
+                                            egui::RichText::new(format!("{:>digits$}", i + 1))
+                                                .monospace()
+                                                .weak(),
+                                        );
+                                        ui.add(
+                                            egui::Label::new(egui::RichText::new(line).monospace())
+                                                .extend(),
+                                        );
+                                    });
+                                }
+                            });
+                        }
+                        Some(PreviewData::Table(table)) => {
+                            switch_table_view = Self::table_toggle(ui, true, true);
+                            let separator = match table.delimiter {
+                                b'\t' => "Tab".to_string(),
+                                other => format!("'{}'", other as char),
+                            };
+                            ui.horizontal(|ui| {
+                                ui.weak(format!(
+                                    "{} rows · {} columns · {} separated",
+                                    table.row_count(),
+                                    table.column_count(),
+                                    separator
+                                ));
+                                if !table.is_loaded() {
+                                    ui.add(
+                                        egui::ProgressBar::new(table.progress())
+                                            .desired_width(80.0)
+                                            .show_percentage(),
+                                    );
+                                }
+                            });
+                            ui.separator();
+
+                            // Only the rows in view are laid out, like the file list
+                            let row_height = ui.text_style_height(&egui::TextStyle::Body) + 4.0;
+                            let column = egui_extras::Column::initial(100.0)
+                                .at_least(30.0)
+                                .resizable(true)
+                                .clip(true);
+                            let mut sort_clicked = None;
+                            egui::ScrollArea::horizontal().show(ui, |ui| {
+                                egui_extras::TableBuilder::new(ui)
+                                    .striped(true)
+                                    .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+                                    .column(egui_extras::Column::auto().at_least(30.0))
+                                    .columns(column, table.column_count())
+                                    .header(row_height, |mut header| {
+                                        header.col(|ui| {
+                                            ui.weak("#");
+                                        });
+                                        for i in 0..table.column_count() {
+                                            let name = table.headers.get(i).cloned();
+                                            let arrow = match table.sort {
+                                                Some((c, true)) if c == i => " ⏶",
+                                                Some((c, false)) if c == i => " ⏷",
+                                                _ => "",
+                                            };
+                                            let label = egui::RichText::new(format!(
+                                                "{}{}",
+                                                name.unwrap_or_default(),
+                                                arrow
+                                            ));
+                                            let button = egui::Button::new(label.strong());
+                                            header.col(|ui| {
+                                                if ui
+                                                    .add_enabled(table.is_loaded(), button)
+                                                    .on_disabled_hover_text("Sorts once loaded")
+                                                    .clicked()
+                                                {
+                                                    sort_clicked = Some(i);
+                                                }
+                                            });
+                                        }
+                                    })
+                                    .body(|body| {
+                                        body.rows(row_height, table.row_count(), |mut row| {
+                                            let index = row.index();
+                                            row.col(|ui| {
+                                                ui.weak((index + 1).to_string());
+                                            });
+                                            for i in 0..table.column_count() {
+                                                row.col(|ui| {
+                                                    ui.label(table.cell(index, i));
+                                                });
+                                            }
+                                        });
+                                    });
+                            });
+                            if let Some(column) = sort_clicked {
+                                table.sort_by(column);
+                            }
+                        }
+                        Some(PreviewData::Hex(file)) => {
+                            let jump = std::mem::take(&mut self.hex_jump)
+                                .then_some(self.hex_cursor / BYTES_PER_ROW);
+                            ui.horizontal(|ui| {
+                                let resp = ui.add(
+                                    egui::TextEdit::singleline(&mut self.hex_find)
+                                        .hint_text("Hex bytes or \"text\"")
+                                        .desired_width(140.0),
                                 );
+                                let entered = resp.lost_focus()
+                                    && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                                if ui.button("Find Next").clicked() || entered {
+                                    self.hex_status = None;
+                                    match hex::parse_pattern(&self.hex_find) {
+                                        Ok(pattern) => {
+                                            // A new find replaces one still running
+                                            if let Some(cancel) = self.hex_search.take() {
+                                                cancel.store(true, Ordering::Relaxed);
+                                            }
+                                            self.hex_search_id += 1;
+                                            let cancel = Arc::new(AtomicBool::new(false));
+                                            self.hex_search = Some(cancel.clone());
+                                            let id = self.hex_search_id;
+                                            let tx = self.hex_search_tx.clone();
+                                            let path = file.path().to_path_buf();
+                                            let from = self.hex_cursor;
+                                            let ctx = ui.ctx().clone();
+                                            thread::spawn(move || {
+                                                let found = hex::find(&path, &pattern, from, &cancel);
+                                                let _ = tx.send((id, found));
+                                                ctx.request_repaint();
+                                            });
+                                        }
+                                        Err(e) => self.hex_status = Some(e),
+                                    }
+                                }
+                                if self.hex_search.is_some() {
+                                    ui.spinner();
+                                    if ui.button("Cancel").clicked() {
+                                        if let Some(cancel) = self.hex_search.take() {
+                                            cancel.store(true, Ordering::Relaxed);
+                                        }
+                                        self.hex_search_id += 1;
+                                    }
+                                }
+                                if let Some(status) = &self.hex_status {
+                                    ui.colored_label(egui::Color32::from_rgb(220, 80, 80), status);
+                                }
+                            });
+                            ui.weak(format!(
+                                "Offset 0x{:X} · {}",
+                                self.hex_cursor,
+                                format_size(file.len() as u64, DECIMAL)
+                            ));
+
+                            // Enough for the widest type the inspector shows
+                            let at_cursor = file.read(self.hex_cursor, 8);
+                            egui::CollapsingHeader::new("Data Inspector")
+                                .default_open(true)
+                                .show(ui, |ui| {
+                                    egui::Grid::new("hex_inspector").striped(true).show(ui, |ui| {
+                                        for (name, value) in hex::inspect(&at_cursor) {
+                                            ui.label(name);
+                                            ui.monospace(value);
+                                            ui.end_row();
+                                        }
+                                    });
+                                });
+                            ui.separator();
+
+                            let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+                            let pitch = row_height + ui.spacing().item_spacing.y;
+                            let mut scroll = egui::ScrollArea::both().auto_shrink([false; 2]);
+                            if let Some(row) = jump {
+                                scroll = scroll
+                                    .vertical_scroll_offset(row.saturating_sub(4) as f32 * pitch);
+                            }
+                            let highlight = ui.visuals().selection.bg_fill;
+                            scroll.show_rows(ui, row_height, file.rows(), |ui, rows| {
+                                // One read for every row in view
+                                let window =
+                                    file.read(rows.start * BYTES_PER_ROW, rows.len() * BYTES_PER_ROW);
+                                for (row, bytes) in rows.zip(window.chunks(BYTES_PER_ROW)) {
+                                    let start = row * BYTES_PER_ROW;
+                                    ui.horizontal(|ui| {
+                                        ui.label(
+                                            egui::RichText::new(format!("{:08X}", start))
+                                                .monospace()
+                                                .weak(),
+                                        );
+                                        // Hex and ASCII cells both select the byte they show
+                                        let mut cell = |ui: &mut egui::Ui, i: usize, text: String| {
+                                            let mut text = egui::RichText::new(text).monospace();
+                                            if start + i == self.hex_cursor {
+                                                text = text.background_color(highlight);
+                                            }
+                                            let label =
+                                                egui::Label::new(text).sense(egui::Sense::click());
+                                            if ui.add(label).clicked() {
+                                                self.hex_cursor = start + i;
+                                            }
+                                        };
+                                        for i in 0..BYTES_PER_ROW {
+                                            if i == BYTES_PER_ROW / 2 {
+                                                ui.add_space(4.0);
+                                            }
+                                            match bytes.get(i) {
+                                                Some(byte) => cell(ui, i, format!("{:02X}", byte)),
+                                                None => {
+                                                    ui.monospace("  ");
+                                                }
+                                            }
+                                        }
+                                        ui.add_space(8.0);
+                                        ui.spacing_mut().item_spacing.x = 0.0;
+                                        for (i, &byte) in bytes.iter().enumerate() {
+                                            let shown = if byte.is_ascii_graphic() || byte == b' ' {
+                                                byte as char
+                                            } else {
+                                                '.'
+                                            };
+                                            cell(ui, i, shown.to_string());
+                                        }
+                                    });
+                                }
                             });
                         }
-                        Some(PreviewData::Image(path)) => {
+                        Some(PreviewData::Image(path, meta)) => {
                             let uri =
                                 format!("file://{}", path.to_string_lossy().replace("\\", "/"));
                             
@@ -1141,127 +3619,170 @@ impl eframe::App for ExplorerApp {
                                 self.image_zoom = self.image_zoom.clamp(0.1, 5.0);
                             }
 
+                            egui::TopBottomPanel::bottom("image_info")
+                                .resizable(false)
+                                .show_inside(ui, |ui| {
+                                    Self::image_info(ui, meta);
+                                });
+
                             egui::ScrollArea::both()
                                 .auto_shrink([false; 2])
                                 .show(ui, |ui| {
                                     // Use available_size to determine the base fit, then apply zoom
                                     let base_size = ui.available_size();
                                     let zoomed_size = base_size * self.image_zoom;
-                                    
-                                    ui.centered_and_justified(|ui| {
-                                        ui.add(egui::Image::new(uri)
-                                            .fit_to_exact_size(zoomed_size)
-                                            .maintain_aspect_ratio(true)
-                                        );
-                                    });
-                                });
-                        }
-                        Some(PreviewData::Pdf(path)) => {
-                            ui.centered_and_justified(|ui| {
-                                ui.vertical(|ui| {
-                                    ui.label("📄 PDF File");
-                                    ui.label(path.file_name().unwrap_or_default().to_string_lossy());
-                                    ui.add_space(10.0);
-                                    if ui.button("Open with Default App").clicked() {
-                                        let _ = open::that(path);
+                                    let orientation = meta.orientation;
+                                    let turned = orientation != Orientation::UPRIGHT;
+
+                                    match meta.upright_size() {
+                                        // egui draws pixels as stored, so turn photos upright here
+                                        Some((width, height)) if turned => {
+                                            let upright = egui::vec2(width as f32, height as f32);
+                                            let size = upright * (zoomed_size / upright).min_elem();
+                                            let (area, _) = ui.allocate_exact_size(
+                                                zoomed_size.max(size),
+                                                egui::Sense::hover(),
+                                            );
+                                            let stored = if orientation.swaps_axes() {
+                                                egui::vec2(size.y, size.x)
+                                            } else {
+                                                size
+                                            };
+                                            let angle = (orientation.degrees() as f32).to_radians();
+                                            let mut image = egui::Image::new(uri)
+                                                .rotate(angle, egui::Vec2::splat(0.5));
+                                            if orientation.mirrored() {
+                                                let flipped = egui::Rect::from_min_max(
+                                                    egui::pos2(1.0, 0.0),
+                                                    egui::pos2(0.0, 1.0),
+                                                );
+                                                image = image.uv(flipped);
+                                            }
+                                            image.paint_at(
+                                                ui,
+                                                egui::Rect::from_center_size(area.center(), stored),
+                                            );
+                                        }
+                                        _ => {
+                                            ui.centered_and_justified(|ui| {
+                                                ui.add(egui::Image::new(uri)
+                                                    .fit_to_exact_size(zoomed_size)
+                                                    .maintain_aspect_ratio(true)
+                                                );
+                                            });
+                                        }
                                     }
                                 });
-                            });
+                        }
+                        Some(PreviewData::Pdf(pdf)) => {
+                            Self::show_pdf_preview(ui, pdf, &mut self.image_zoom, &self.pdf_tx);
                         }
                         None => {}
                     }
+
+                    if let Some(table_view) = switch_table_view {
+                        self.table_view = table_view;
+                        self.load_preview_for(self.preview_path.clone());
+                    }
+                    if let Some(target) = open_link {
+                        if target.is_dir() {
+                            self.navigate_to(target, true);
+                        } else if target.exists() {
+                            self.load_preview_for(Some(target));
+                        } else {
+                            self.error_message =
+                                Some(format!("Link target not found: {}", target.display()));
+                        }
+                    }
                 });
         }
 
         // --- Main Content Area ---
         egui::CentralPanel::default().show(ctx, |ui| {
-            if self.is_loading {
+            // Search results are still shown as a list in the usage view
+            let usage_view = self.view_mode == ViewMode::Usage
+                && !self.showing_search_results
+                && self.active_saved_search.is_none();
+            if usage_view && self.content_results.is_none() {
+                self.show_usage_view(ui);
+            } else if self.is_loading {
                 ui.centered_and_justified(|ui| {
                     ui.spinner();
                 });
+            } else if self.content_results.is_some() {
+                self.show_content_results(ui);
             } else {
                 let mut action_to_perform = None; // (ActionType, Index)
                 let mut selection_to_make = None;
+                let mut in_view = Vec::new();
 
-                // Filter entries based on search query
+                // Filter entries based on search query. Search results already matched
+                // their own query, so they're shown as they are.
+                let query = self.search_query.to_lowercase();
                 let filtered_indices: Vec<usize> = self
                     .entries
                     .iter()
                     .enumerate()
                     .filter(|(_, e)| {
-                        self.search_query.is_empty()
-                            || e.name
-                                .to_lowercase()
-                                .contains(&self.search_query.to_lowercase())
+                        query.is_empty()
+                            || self.showing_search_results
+                            || e.name.to_lowercase().contains(&query)
                     })
                     .map(|(i, _)| i)
                     .collect();
 
-                egui::ScrollArea::vertical().show(ui, |ui| {
-                    match self.view_mode {
-                        ViewMode::List => {
-                            egui::Grid::new("file_grid")
-                                .striped(true)
-                                .min_col_width(20.0)
-                                .spacing([10.0, 4.0])
-                                .show(ui, |ui| {
-                                    // Headers (Sortable)
-                                    if ui.button(egui::RichText::new("Name").strong()).clicked() {
-                                        if self.sort_column == SortColumn::Name {
-                                            self.sort_order =
-                                                if self.sort_order == SortOrder::Ascending {
-                                                    SortOrder::Descending
-                                                } else {
-                                                    SortOrder::Ascending
-                                                };
-                                        } else {
-                                            self.sort_column = SortColumn::Name;
-                                            self.sort_order = SortOrder::Ascending;
-                                        }
-                                        self.sort_entries();
-                                        self.save_state();
-                                    }
-                                    if ui.button(egui::RichText::new("Size").strong()).clicked() {
-                                        if self.sort_column == SortColumn::Size {
-                                            self.sort_order =
-                                                if self.sort_order == SortOrder::Ascending {
-                                                    SortOrder::Descending
-                                                } else {
-                                                    SortOrder::Ascending
-                                                };
-                                        } else {
-                                            self.sort_column = SortColumn::Size;
-                                            self.sort_order = SortOrder::Ascending;
-                                        }
-                                        self.sort_entries();
-                                        self.save_state();
-                                    }
-                                    if ui
-                                        .button(egui::RichText::new("Modified").strong())
-                                        .clicked()
-                                    {
-                                        if self.sort_column == SortColumn::Modified {
-                                            self.sort_order =
-                                                if self.sort_order == SortOrder::Ascending {
-                                                    SortOrder::Descending
-                                                } else {
-                                                    SortOrder::Ascending
-                                                };
-                                        } else {
-                                            self.sort_column = SortColumn::Modified;
-                                            self.sort_order = SortOrder::Ascending;
-                                        }
-                                        self.sort_entries();
-                                        self.save_state();
-                                    }
-                                    ui.end_row();
+                let nav_pressed = ctx.input(|i| {
+                    i.key_pressed(egui::Key::ArrowDown)
+                        || i.key_pressed(egui::Key::ArrowUp)
+                        || i.key_pressed(egui::Key::Home)
+                        || i.key_pressed(egui::Key::End)
+                }) && !ctx.wants_keyboard_input();
+                // Position of the selection among the visible rows, for keyboard scrolling
+                let selected_pos = self
+                    .selected_entry
+                    .and_then(|sel| filtered_indices.iter().position(|&i| i == sel))
+                    .filter(|_| nav_pressed);
+                let mut sort_clicked = None;
+
+                // Only the rows in view are laid out, so huge folders stay responsive
+                match self.view_mode {
+                    ViewMode::List | ViewMode::Usage => {
+                        let row_height = ui.text_style_height(&egui::TextStyle::Body) + 6.0;
+                        let mut table = egui_extras::TableBuilder::new(ui)
+                            .striped(true)
+                            .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+                            .column(egui_extras::Column::remainder().at_least(120.0).clip(true))
+                            .column(egui_extras::Column::auto().at_least(70.0))
+                            .column(egui_extras::Column::auto().at_least(110.0));
+                        if let Some(pos) = selected_pos {
+                            table = table.scroll_to_row(pos, None);
+                        }
 
-                                    for &i in &filtered_indices {
-                                        let entry = &self.entries[i];
-                                        let (icon, icon_color) = self.get_icon_for_entry(entry);
-                                        let is_selected = self.selected_entry == Some(i);
-                                        let is_renaming = self.renaming_index == Some(i);
+                        table
+                            .header(row_height, |mut header| {
+                                // Headers (Sortable)
+                                for (label, column) in [
+                                    ("Name", SortColumn::Name),
+                                    ("Size", SortColumn::Size),
+                                    ("Modified", SortColumn::Modified),
+                                ] {
+                                    header.col(|ui| {
+                                        if ui.button(egui::RichText::new(label).strong()).clicked() {
+                                            sort_clicked = Some(column);
+                                        }
+                                    });
+                                }
+                            })
+                            .body(|body| {
+                                body.rows(row_height, filtered_indices.len(), |mut row| {
+                                    let i = filtered_indices[row.index()];
+                                    in_view.push(i);
+                                    let entry = &self.entries[i];
+                                    let (icon, icon_color) = self.get_icon_for_entry(entry);
+                                    let is_selected = self.selected_entry == Some(i);
+                                    let is_renaming = self.renaming_index == Some(i);
 
+                                    row.col(|ui| {
                                         if is_renaming {
                                             let re =
                                                 ui.text_edit_singleline(&mut self.rename_buffer);
@@ -1274,179 +3795,135 @@ impl eframe::App for ExplorerApp {
                                             }
                                             re.request_focus();
                                         } else {
-                                            ui.horizontal(|ui| {
-                                                ui.spacing_mut().item_spacing.x = 4.0;
-                                                ui.colored_label(icon_color, icon);
-                                                let name_resp =
-                                                    ui.selectable_label(is_selected, &entry.name);
-
-                                                if is_selected
-                                                    && ctx.input(|i| {
-                                                        i.key_pressed(egui::Key::ArrowDown)
-                                                            || i.key_pressed(egui::Key::ArrowUp)
-                                                            || i.key_pressed(egui::Key::Home)
-                                                            || i.key_pressed(egui::Key::End)
-                                                    })
-                                                {
-                                                    name_resp.scroll_to_me(None);
-                                                }
+                                            ui.spacing_mut().item_spacing.x = 4.0;
+                                            ui.colored_label(icon_color, icon);
+                                            let name_resp =
+                                                ui.selectable_label(is_selected, &entry.name);
 
-                                                if name_resp.clicked() {
-                                                    selection_to_make = Some(i);
-                                                    if self.renaming_index.is_some() {
-                                                        self.renaming_index = None;
-                                                    }
-                                                }
-                                                if name_resp.double_clicked() {
-                                                    action_to_perform = Some(("open", i));
+                                            if name_resp.clicked() {
+                                                selection_to_make = Some(i);
+                                                if self.renaming_index.is_some() {
+                                                    self.renaming_index = None;
                                                 }
+                                            }
+                                            if name_resp.double_clicked() {
+                                                action_to_perform = Some(("open", i));
+                                            }
 
-                                                name_resp.context_menu(|ui| {
-                                                    if ui.button("Open").clicked() {
-                                                        action_to_perform = Some(("open", i));
-                                                        ui.close_menu();
-                                                    }
-                                                    ui.separator();
-                                                                                                    if ui.button("Compress to Zip").clicked() {
-                                                                                                        action_to_perform = Some(("compress", i));
-                                                                                                        ui.close_menu();
-                                                                                                    }
-                                                                                                    if entry.name.ends_with(".zip") {
-                                                                                                        if ui.button("Extract Here").clicked() {
-                                                                                                            action_to_perform = Some(("extract", i));
-                                                                                                            ui.close_menu();
-                                                                                                        }
-                                                                                                    }
-                                                                                                    ui.separator();
-                                                                                                    if entry.file_type == FileType::File {
-                                                                                                        if ui.button("🔒 Encrypt").clicked() {
-                                                                                                            action_to_perform = Some(("request_encrypt", i));
-                                                                                                            ui.close_menu();
-                                                                                                        }
-                                                                                                        if entry.name.ends_with(".enc") {
-                                                                                                            if ui.button("🔓 Decrypt").clicked() {
-                                                                                                                action_to_perform = Some(("request_decrypt", i));
-                                                                                                                ui.close_menu();
-                                                                                                            }
-                                                                                                        }
-                                                                                                    }
-                                                                                                    ui.separator();                                                    if ui.button("Rename (F2)").clicked() {
-                                                        action_to_perform = Some(("rename", i));
-                                                        ui.close_menu();
-                                                    }
-                                                    if ui.button("Copy (Ctrl+C)").clicked() {
-                                                        action_to_perform = Some(("copy", i));
-                                                        ui.close_menu();
-                                                    }
-                                                    if ui.button("Delete (Del)").clicked() {
-                                                        action_to_perform = Some(("delete", i));
-                                                        ui.close_menu();
-                                                    }
-                                                });
+                                            name_resp.context_menu(|ui| {
+                                                entry_context_menu(ui, entry, i, &mut action_to_perform);
                                             });
                                         }
-                                        
-                                        // Format size and date on the fly
-                                        let meta_color = egui::Color32::from_rgb(108, 112, 134);
-                                        let size_str = if entry.file_type == FileType::Directory {
-                                            "-".to_string()
-                                        } else {
-                                            format_size(entry.size, DECIMAL)
-                                        };
-                                        let date_str = if entry.modified > 0 {
-                                             let dt = Local.timestamp_opt(entry.modified, 0).unwrap();
-                                             dt.format("%Y-%m-%d %H:%M").to_string()
-                                        } else {
-                                            String::new()
-                                        };
+                                    });
 
-                                        ui.colored_label(meta_color, size_str);
+                                    // Format size and date on the fly
+                                    let meta_color = egui::Color32::from_rgb(108, 112, 134);
+                                    let folder_size = self.folder_sizes.get(&entry.path);
+                                    let size_str = if entry.file_type != FileType::Directory {
+                                        format_size(entry.size, DECIMAL)
+                                    } else if let Some(size) = folder_size {
+                                        format_size(size.bytes, DECIMAL)
+                                    } else if self.folder_sizes_pending.contains(&entry.path) {
+                                        "…".to_string()
+                                    } else {
+                                        "-".to_string()
+                                    };
+                                    let date_str = if entry.modified > 0 {
+                                         let dt = Local.timestamp_opt(entry.modified, 0).unwrap();
+                                         dt.format("%Y-%m-%d %H:%M").to_string()
+                                    } else {
+                                        String::new()
+                                    };
+
+                                    row.col(|ui| {
+                                        let resp = ui.colored_label(meta_color, size_str);
+                                        if let Some(size) = folder_size {
+                                            resp.on_hover_text(format!(
+                                                "{} files, {} folders",
+                                                size.files, size.dirs
+                                            ));
+                                        }
+                                    });
+                                    row.col(|ui| {
                                         ui.colored_label(meta_color, date_str);
-                                        ui.end_row();
-                                    }
+                                    });
                                 });
+                            });
+                    }
+                    ViewMode::Grid => {
+                        // Fixed size cells, laid out row by row so show_rows can skip the rest
+                        let cell = egui::vec2(90.0, 90.0);
+                        let gap = 15.0;
+                        let columns =
+                            (((ui.available_width() + gap) / (cell.x + gap)).floor() as usize).max(1);
+                        let rows = filtered_indices.len().div_ceil(columns);
+                        let row_pitch = cell.y + ui.spacing().item_spacing.y;
+
+                        let mut scroll = egui::ScrollArea::vertical().auto_shrink([false; 2]);
+                        if let Some(pos) = selected_pos {
+                            let top = (pos / columns) as f32 * row_pitch;
+                            let (offset, viewport) = self.grid_scroll;
+                            if top < offset {
+                                scroll = scroll.vertical_scroll_offset(top);
+                            } else if top + row_pitch > offset + viewport {
+                                scroll = scroll.vertical_scroll_offset(top + row_pitch - viewport);
+                            }
                         }
-                        ViewMode::Grid => {
-                            ui.horizontal_wrapped(|ui| {
-                                ui.spacing_mut().item_spacing = egui::vec2(15.0, 15.0);
-                                for &i in &filtered_indices {
-                                    let entry = &self.entries[i];
-                                    let (icon, icon_color) = self.get_icon_for_entry(entry);
-                                    let is_selected = self.selected_entry == Some(i);
 
-                                    // Use a fixed size container for each item to ensure a perfect grid
-                                    ui.allocate_ui(egui::vec2(90.0, 90.0), |ui| {
-                                        ui.vertical_centered(|ui| {
-                                            let icon_rich = egui::RichText::new(icon)
-                                                .size(40.0)
-                                                .color(icon_color);
-                                            
-                                            let resp = ui.selectable_label(is_selected, icon_rich);
+                        let output = scroll.show_rows(ui, cell.y, rows, |ui, row_range| {
+                            for row in row_range {
+                                ui.horizontal(|ui| {
+                                    ui.spacing_mut().item_spacing.x = gap;
+                                    let first = row * columns;
+                                    let last = (first + columns).min(filtered_indices.len());
+                                    for &i in &filtered_indices[first..last] {
+                                        in_view.push(i);
+                                        let entry = &self.entries[i];
+                                        let (icon, icon_color) = self.get_icon_for_entry(entry);
+                                        let is_selected = self.selected_entry == Some(i);
+
+                                        ui.allocate_ui_with_layout(
+                                            cell,
+                                            egui::Layout::top_down(egui::Align::Center),
+                                            |ui| {
+                                                ui.set_min_size(cell);
+                                                let icon_rich = egui::RichText::new(icon)
+                                                    .size(40.0)
+                                                    .color(icon_color);
 
-                                            if resp.clicked() {
-                                                selection_to_make = Some(i);
-                                            }
-                                            if resp.double_clicked() {
-                                                action_to_perform = Some(("open", i));
-                                            }
+                                                let resp = ui.selectable_label(is_selected, icon_rich);
 
-                                            resp.context_menu(|ui| {
-                                                if ui.button("Open").clicked() {
-                                                    action_to_perform = Some(("open", i));
-                                                    ui.close_menu();
-                                                }
-                                                ui.separator();
-                                                                                                if ui.button("Compress to Zip").clicked() {
-                                                                                                    action_to_perform = Some(("compress", i));
-                                                                                                    ui.close_menu();
-                                                                                                }
-                                                                                                if entry.name.ends_with(".zip") {
-                                                                                                    if ui.button("Extract Here").clicked() {
-                                                                                                        action_to_perform = Some(("extract", i));
-                                                                                                        ui.close_menu();
-                                                                                                    }
-                                                                                                }
-                                                                                                ui.separator();
-                                                                                                if entry.file_type == FileType::File {
-                                                                                                    if ui.button("🔒 Encrypt").clicked() {
-                                                                                                        action_to_perform = Some(("request_encrypt", i));
-                                                                                                        ui.close_menu();
-                                                                                                    }
-                                                                                                    if entry.name.ends_with(".enc") {
-                                                                                                        if ui.button("🔓 Decrypt").clicked() {
-                                                                                                            action_to_perform = Some(("request_decrypt", i));
-                                                                                                            ui.close_menu();
-                                                                                                        }
-                                                                                                    }
-                                                                                                }
-                                                                                                ui.separator();                                                if ui.button("Rename (F2)").clicked() {
-                                                    action_to_perform = Some(("rename", i));
-                                                    ui.close_menu();
-                                                }
-                                                if ui.button("Copy (Ctrl+C)").clicked() {
-                                                    action_to_perform = Some(("copy", i));
-                                                    ui.close_menu();
+                                                if resp.clicked() {
+                                                    selection_to_make = Some(i);
                                                 }
-                                                if ui.button("Delete (Del)").clicked() {
-                                                    action_to_perform = Some(("delete", i));
-                                                    ui.close_menu();
+                                                if resp.double_clicked() {
+                                                    action_to_perform = Some(("open", i));
                                                 }
-                                            });
 
-                                            ui.add(
-                                                egui::Label::new(
-                                                    egui::RichText::new(&entry.name).size(11.0),
-                                                )
-                                                .truncate(),
-                                            );
-                                        });
-                                    });
-                                }
-                            });
-                        }
+                                                resp.context_menu(|ui| {
+                                                    entry_context_menu(ui, entry, i, &mut action_to_perform);
+                                                });
+
+                                                ui.add(
+                                                    egui::Label::new(
+                                                        egui::RichText::new(&entry.name).size(11.0),
+                                                    )
+                                                    .truncate(),
+                                                );
+                                            },
+                                        );
+                                    }
+                                });
+                            }
+                        });
+                        self.grid_scroll = (output.state.offset.y, output.inner_rect.height());
                     }
-                });
+                }
 
+                self.sniff_entries(&in_view);
+                if let Some(column) = sort_clicked {
+                    self.toggle_sort(column);
+                }
                 if let Some(idx) = selection_to_make {
                     self.select_entry(Some(idx));
                 }
@@ -1459,6 +3936,13 @@ impl eframe::App for ExplorerApp {
                             self.start_rename();
                         }
                         "confirm_rename" => self.confirm_rename(),
+                        "folder_size" => {
+                            if let Some(entry) = self.entries.get(idx) {
+                                let path = entry.path.clone();
+                                self.folder_sizes.remove(&path);
+                                self.compute_folder_size(path);
+                            }
+                        }
                         "copy" => {
                             self.select_entry(Some(idx));
                             self.copy_selected();
@@ -1490,4 +3974,109 @@ impl eframe::App for ExplorerApp {
                                                                                         }            }
         });
     }
-}
\ No newline at end of file
+}
+
+// Optional size limit edited in MB
+fn size_bound_ui(ui: &mut egui::Ui, bound: &mut Option<u64>) {
+    ui.horizontal(|ui| {
+        let mut enabled = bound.is_some();
+        if ui.checkbox(&mut enabled, "").changed() {
+            *bound = if enabled { Some(0) } else { None };
+        }
+        if let Some(bytes) = bound {
+            let mut mb = *bytes as f64 / 1_000_000.0;
+            if ui
+                .add(egui::DragValue::new(&mut mb).range(0.0..=f64::MAX).suffix(" MB"))
+                .changed()
+            {
+                *bytes = (mb * 1_000_000.0) as u64;
+            }
+        }
+    });
+}
+
+// Optional date limit stored as unix seconds. Upper bounds cover the whole selected day.
+fn date_bound_ui(ui: &mut egui::Ui, id: &str, bound: &mut Option<i64>, end_of_day: bool) {
+    ui.horizontal(|ui| {
+        let mut enabled = bound.is_some();
+        if ui.checkbox(&mut enabled, "").changed() {
+            *bound = if enabled {
+                Some(Local::now().timestamp())
+            } else {
+                None
+            };
+        }
+        if let Some(timestamp) = bound {
+            let mut date = Local
+                .timestamp_opt(*timestamp, 0)
+                .single()
+                .map(|dt| dt.date_naive())
+                .unwrap_or_else(|| Local::now().date_naive());
+            ui.add(egui_extras::DatePickerButton::new(&mut date).id_salt(id));
+            if let Some(ts) = day_boundary(date, end_of_day) {
+                *timestamp = ts;
+            }
+        }
+    });
+}
+
+fn day_boundary(date: NaiveDate, end_of_day: bool) -> Option<i64> {
+    let time = if end_of_day {
+        date.and_hms_opt(23, 59, 59)?
+    } else {
+        date.and_hms_opt(0, 0, 0)?
+    };
+    Local
+        .from_local_datetime(&time)
+        .earliest()
+        .map(|dt| dt.timestamp())
+}
+
+fn entry_context_menu(
+    ui: &mut egui::Ui,
+    entry: &FileEntry,
+    i: usize,
+    action: &mut Option<(&'static str, usize)>,
+) {
+    if ui.button("Open").clicked() {
+        *action = Some(("open", i));
+        ui.close_menu();
+    }
+    ui.separator();
+    if ui.button("Compress to Zip").clicked() {
+        *action = Some(("compress", i));
+        ui.close_menu();
+    }
+    if entry.file_type == FileType::Directory && ui.button("Calculate Size").clicked() {
+        *action = Some(("folder_size", i));
+        ui.close_menu();
+    }
+    if entry.name.ends_with(".zip") && ui.button("Extract Here").clicked() {
+        *action = Some(("extract", i));
+        ui.close_menu();
+    }
+    ui.separator();
+    if entry.file_type == FileType::File {
+        if ui.button("🔒 Encrypt").clicked() {
+            *action = Some(("request_encrypt", i));
+            ui.close_menu();
+        }
+        if entry.name.ends_with(".enc") && ui.button("🔓 Decrypt").clicked() {
+            *action = Some(("request_decrypt", i));
+            ui.close_menu();
+        }
+    }
+    ui.separator();
+    if ui.button("Rename (F2)").clicked() {
+        *action = Some(("rename", i));
+        ui.close_menu();
+    }
+    if ui.button("Copy (Ctrl+C)").clicked() {
+        *action = Some(("copy", i));
+        ui.close_menu();
+    }
+    if ui.button("Delete (Del)").clicked() {
+        *action = Some(("delete", i));
+        ui.close_menu();
+    }
+}
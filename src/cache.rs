@@ -0,0 +1,41 @@
+use crate::filesystem::FileEntry;
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+const CAPACITY: usize = 32;
+
+struct CachedListing {
+    path: PathBuf,
+    mtime: SystemTime,
+    entries: Vec<FileEntry>,
+}
+
+// Recently loaded directory listings, most recently used first.
+//
+// A listing is only as fresh as its folder's mtime: callers show it straight
+// away and ask the loader to re-read the folder if the mtime has moved on.
+#[derive(Default)]
+pub struct ListingCache {
+    listings: VecDeque<CachedListing>,
+}
+
+impl ListingCache {
+    /// Returns the cached mtime and entries for `path`, marking it as recently used.
+    pub fn get(&mut self, path: &Path) -> Option<(SystemTime, &[FileEntry])> {
+        let i = self.listings.iter().position(|l| l.path == path)?;
+        let listing = self.listings.remove(i)?;
+        self.listings.push_front(listing);
+        self.listings.front().map(|l| (l.mtime, l.entries.as_slice()))
+    }
+
+    pub fn insert(&mut self, path: PathBuf, mtime: SystemTime, entries: Vec<FileEntry>) {
+        self.listings.retain(|l| l.path != path);
+        self.listings.push_front(CachedListing {
+            path,
+            mtime,
+            entries,
+        });
+        self.listings.truncate(CAPACITY);
+    }
+}
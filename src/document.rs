@@ -0,0 +1,258 @@
+use chardetng::EncodingDetector;
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8};
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+// Encodings offered when converting a file on save
+pub const ENCODING_CHOICES: [&Encoding; 12] = [
+    UTF_8,
+    UTF_16LE,
+    UTF_16BE,
+    encoding_rs::WINDOWS_1252,
+    encoding_rs::ISO_8859_2,
+    encoding_rs::WINDOWS_1251,
+    encoding_rs::KOI8_R,
+    encoding_rs::SHIFT_JIS,
+    encoding_rs::EUC_JP,
+    encoding_rs::GBK,
+    encoding_rs::BIG5,
+    encoding_rs::EUC_KR,
+];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LineEnding {
+    Lf,
+    CrLf,
+    Cr,
+}
+
+impl LineEnding {
+    pub const ALL: [LineEnding; 3] = [LineEnding::Lf, LineEnding::CrLf, LineEnding::Cr];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            LineEnding::Lf => "LF",
+            LineEnding::CrLf => "CRLF",
+            LineEnding::Cr => "CR",
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::CrLf => "\r\n",
+            LineEnding::Cr => "\r",
+        }
+    }
+
+    // The most common style in `text`, and whether more than one occurs
+    fn detect(text: &str) -> (LineEnding, bool) {
+        let crlf = text.matches("\r\n").count();
+        let lf = text.matches('\n').count() - crlf;
+        let cr = text.matches('\r').count() - crlf;
+        let mixed = [crlf, lf, cr].iter().filter(|&&n| n > 0).count() > 1;
+        let ending = if crlf > lf && crlf >= cr {
+            LineEnding::CrLf
+        } else if cr > lf {
+            LineEnding::Cr
+        } else {
+            LineEnding::Lf
+        };
+        (ending, mixed)
+    }
+}
+
+// The bytes-on-disk side of a document, kept apart from the text being edited
+#[derive(Clone, Copy, PartialEq)]
+struct Format {
+    encoding: &'static Encoding,
+    bom: bool,
+    line_ending: LineEnding,
+}
+
+// A text file opened in the preview editor.
+//
+// Remembers what was last read from or written to disk, so the UI can tell unsaved
+// edits apart and notice when another program changed the file in the meantime.
+// The text is edited with `\n` line breaks; the encoding, BOM and line ending style
+// are detected on load and applied again on save, so converting is just changing them.
+pub struct TextDocument {
+    pub content: String,
+    pub encoding: &'static Encoding,
+    pub bom: bool,
+    pub line_ending: LineEnding,
+    pub mixed_endings: bool, // Normalized to `line_ending` on save
+    pub lossy: bool,         // Some bytes didn't decode, saving would corrupt them
+    path: PathBuf,
+    saved: String,
+    saved_format: Format,
+    disk_stamp: Option<(SystemTime, u64)>, // Modified time and length as of load/save
+}
+
+fn disk_stamp(path: &Path) -> Option<(SystemTime, u64)> {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+impl TextDocument {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        // Stamp first: a write racing the read then shows up as a change later
+        let disk_stamp = disk_stamp(path);
+        let bytes = fs::read(path).map_err(|e| e.to_string())?;
+
+        let (encoding, bom_len) = detect_encoding(&bytes);
+        let (text, lossy) = encoding.decode_without_bom_handling(&bytes[bom_len..]);
+        let (line_ending, mixed_endings) = LineEnding::detect(&text);
+        let content = text.replace("\r\n", "\n").replace('\r', "\n");
+
+        let format = Format {
+            encoding,
+            bom: bom_len > 0,
+            line_ending,
+        };
+        Ok(Self {
+            saved: content.clone(),
+            content,
+            encoding,
+            bom: format.bom,
+            line_ending,
+            mixed_endings,
+            lossy,
+            path: path.to_path_buf(),
+            saved_format: format,
+            disk_stamp,
+        })
+    }
+
+    fn format(&self) -> Format {
+        Format {
+            encoding: self.encoding,
+            bom: self.bom,
+            line_ending: self.line_ending,
+        }
+    }
+
+    /// Encoding the file had when loaded or last saved.
+    pub fn saved_encoding(&self) -> &'static Encoding {
+        self.saved_format.encoding
+    }
+
+    pub fn supports_bom(&self) -> bool {
+        [UTF_8, UTF_16LE, UTF_16BE].contains(&self.encoding)
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.content != self.saved || self.format() != self.saved_format
+    }
+
+    // Encodes the text the way it will be written
+    fn encode(&self) -> Result<Vec<u8>, String> {
+        let text = self.content.replace('\n', self.line_ending.as_str());
+        let mut bytes = Vec::with_capacity(text.len() + 3);
+
+        // encoding_rs only decodes UTF-16, so that direction is done by hand
+        if self.encoding == UTF_16LE || self.encoding == UTF_16BE {
+            let little_endian = self.encoding == UTF_16LE;
+            if self.bom {
+                bytes.extend_from_slice(if little_endian { &[0xFF, 0xFE] } else { &[0xFE, 0xFF] });
+            }
+            for unit in text.encode_utf16() {
+                let pair = if little_endian { unit.to_le_bytes() } else { unit.to_be_bytes() };
+                bytes.extend_from_slice(&pair);
+            }
+            return Ok(bytes);
+        }
+
+        if self.bom && self.encoding == UTF_8 {
+            bytes.extend_from_slice(&[0xEF, 0xBB, 0xBF]);
+        }
+        let (encoded, _, unmappable) = self.encoding.encode(&text);
+        if unmappable {
+            return Err(format!(
+                "the text contains characters {} cannot represent",
+                self.encoding.name()
+            ));
+        }
+        bytes.extend_from_slice(&encoded);
+        Ok(bytes)
+    }
+
+    /// True if the file on disk is no longer the one that was loaded or last saved.
+    pub fn changed_on_disk(&self) -> bool {
+        disk_stamp(&self.path) != self.disk_stamp
+    }
+
+    /// Writes to a temporary file next to the original and renames it over it, so a
+    /// failed save never leaves a half-written file behind.
+    pub fn save(&mut self) -> Result<(), String> {
+        if self.lossy {
+            return Err("the file didn't fully decode as this encoding".to_string());
+        }
+        let bytes = self.encode()?;
+        let name = self.path.file_name().ok_or("Invalid file name")?;
+        let temp = self
+            .path
+            .with_file_name(format!(".{}.wander-save", name.to_string_lossy()));
+
+        let write = || -> std::io::Result<()> {
+            let mut file = File::create(&temp)?;
+            file.write_all(&bytes)?;
+            file.sync_all()?;
+            drop(file);
+            if let Ok(metadata) = fs::metadata(&self.path) {
+                fs::set_permissions(&temp, metadata.permissions())?;
+            }
+            fs::rename(&temp, &self.path)
+        };
+        if let Err(e) = write() {
+            let _ = fs::remove_file(&temp);
+            return Err(e.to_string());
+        }
+
+        self.saved = self.content.clone();
+        self.saved_format = self.format();
+        self.mixed_endings = false;
+        self.disk_stamp = disk_stamp(&self.path);
+        Ok(())
+    }
+}
+
+/// UTF-16 without a BOM, told apart by NULs: mostly-ASCII text has one in nearly every
+/// other byte (odd positions for little-endian) and almost none in the rest.
+pub fn bomless_utf16(sample: &[u8]) -> Option<&'static Encoding> {
+    if sample.len() < 4 {
+        return None;
+    }
+    let zeros_at = |parity| {
+        sample.iter().skip(parity).step_by(2).filter(|&&b| b == 0).count() * 2
+    };
+    let (even_zeros, odd_zeros) = (zeros_at(0), zeros_at(1));
+    if odd_zeros > sample.len() * 2 / 5 && even_zeros <= sample.len() / 20 {
+        Some(UTF_16LE)
+    } else if even_zeros > sample.len() * 2 / 5 && odd_zeros <= sample.len() / 20 {
+        Some(UTF_16BE)
+    } else {
+        None
+    }
+}
+
+// A BOM wins, then UTF-16 without one (NUL in every other byte), then UTF-8 if the
+// bytes are valid, then a statistical guess among legacy encodings.
+// Returns the encoding and the length of the BOM to skip.
+fn detect_encoding(bytes: &[u8]) -> (&'static Encoding, usize) {
+    if let Some(found) = Encoding::for_bom(bytes) {
+        return found;
+    }
+
+    if let Some(encoding) = bomless_utf16(&bytes[..bytes.len().min(4096)]) {
+        return (encoding, 0);
+    }
+    if std::str::from_utf8(bytes).is_ok() {
+        return (UTF_8, 0);
+    }
+    let mut detector = EncodingDetector::new();
+    detector.feed(bytes, true);
+    (detector.guess(None, true), 0)
+}
@@ -1,10 +1,15 @@
 use chrono::{DateTime, Local};
 use humansize::{format_size, DECIMAL};
+use ignore::overrides::OverrideBuilder;
+use ignore::WalkBuilder;
+use regex::{Regex, RegexBuilder};
 use std::fs;
-use std::io::Write;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::ops::Range;
 use std::os::windows::fs::MetadataExt;
 use std::path::{Path, PathBuf};
-use std::time::SystemTime;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant, SystemTime};
 use sysinfo::Disks;
 use walkdir::WalkDir;
 use zip::write::FileOptions;
@@ -14,9 +19,10 @@ use aes_gcm::{
 };
 use pbkdf2::pbkdf2_hmac;
 use rand::{RngCore, thread_rng};
+use serde::{Deserialize, Serialize};
 use sha2::Sha256;
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum FileType {
     Directory,
     File,
@@ -24,7 +30,7 @@ pub enum FileType {
     Unknown,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct FileEntry {
     pub name: String,
     pub path: PathBuf,
@@ -32,6 +38,241 @@ pub struct FileEntry {
     pub size: u64,
     pub modified: i64,
     pub is_hidden: bool,
+    #[serde(default)]
+    pub category: FileCategory, // From the extension, or sniffed content if that says nothing
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum FileCategory {
+    Code,
+    WebConfig,
+    Script,
+    Image,
+    Document,
+    Archive,
+    Executable,
+    Media,
+    #[default]
+    Other,
+}
+
+impl FileCategory {
+    pub const ALL: [FileCategory; 9] = [
+        FileCategory::Code,
+        FileCategory::WebConfig,
+        FileCategory::Script,
+        FileCategory::Image,
+        FileCategory::Document,
+        FileCategory::Archive,
+        FileCategory::Executable,
+        FileCategory::Media,
+        FileCategory::Other,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            FileCategory::Code => "Code",
+            FileCategory::WebConfig => "Web & Config",
+            FileCategory::Script => "Scripts",
+            FileCategory::Image => "Images",
+            FileCategory::Document => "Documents",
+            FileCategory::Archive => "Archives",
+            FileCategory::Executable => "Executables",
+            FileCategory::Media => "Media",
+            FileCategory::Other => "Other",
+        }
+    }
+}
+
+pub fn file_category(path: &Path) -> FileCategory {
+    let ext = path
+        .extension()
+        .and_then(|s| s.to_str())
+        .unwrap_or_default()
+        .to_lowercase();
+
+    match ext.as_str() {
+        "rs" | "go" | "c" | "cpp" | "h" | "hpp" | "cc" | "cxx" | "zig" => FileCategory::Code,
+        "js" | "ts" | "jsx" | "tsx" | "html" | "css" | "json" | "yaml" | "yml" | "toml" => {
+            FileCategory::WebConfig
+        }
+        "py" | "rb" | "php" | "lua" | "pl" => FileCategory::Script,
+        "png" | "jpg" | "jpeg" | "gif" | "bmp" | "webp" | "svg" | "ico" => FileCategory::Image,
+        "pdf" | "doc" | "docx" | "txt" | "md" | "odt" => FileCategory::Document,
+        "zip" | "rar" | "7z" | "tar" | "gz" => FileCategory::Archive,
+        "exe" | "msi" | "bat" | "sh" | "ps1" | "app" => FileCategory::Executable,
+        "mp3" | "wav" | "flac" | "mp4" | "mkv" | "avi" => FileCategory::Media,
+        _ => FileCategory::Other,
+    }
+}
+
+// Metadata constraints for recursive search. `None` / empty means "don't care".
+// Timestamps are unix seconds like `FileEntry::modified`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SearchFilter {
+    pub min_size: Option<u64>,
+    pub max_size: Option<u64>,
+    pub modified_after: Option<i64>,
+    pub modified_before: Option<i64>,
+    pub created_after: Option<i64>,
+    pub created_before: Option<i64>,
+    pub categories: Vec<FileCategory>,
+    pub include_hidden: bool,
+}
+
+impl SearchFilter {
+    /// True if anything beyond the defaults is set, i.e. a search makes sense
+    /// even without a name query.
+    pub fn is_active(&self) -> bool {
+        let defaults = SearchFilter {
+            include_hidden: self.include_hidden,
+            ..Default::default()
+        };
+        *self != defaults
+    }
+
+    fn matches(&self, entry: &FileEntry, metadata: &fs::Metadata) -> bool {
+        if entry.is_hidden && !self.include_hidden {
+            return false;
+        }
+
+        // Size and type only make sense for files
+        let is_dir = entry.file_type == FileType::Directory;
+        let wants_files = self.min_size.is_some()
+            || self.max_size.is_some()
+            || !self.categories.is_empty();
+        if is_dir && wants_files {
+            return false;
+        }
+        if self.min_size.is_some_and(|min| entry.size < min)
+            || self.max_size.is_some_and(|max| entry.size > max)
+        {
+            return false;
+        }
+        if !self.categories.is_empty() && !self.categories.contains(&entry.category) {
+            return false;
+        }
+
+        if self.modified_after.is_some_and(|t| entry.modified < t)
+            || self.modified_before.is_some_and(|t| entry.modified > t)
+        {
+            return false;
+        }
+
+        if self.created_after.is_some() || self.created_before.is_some() {
+            // Not every filesystem records a creation time; those entries can't match
+            let Some(created) = metadata
+                .created()
+                .ok()
+                .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
+                .map(|d| d.as_secs() as i64)
+            else {
+                return false;
+            };
+            if self.created_after.is_some_and(|t| created < t)
+                || self.created_before.is_some_and(|t| created > t)
+            {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+// How recursive searches walk the tree. The defaults walk everything, as searches
+// always did; ignore files only apply when asked for.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WalkOptions {
+    pub respect_ignore_files: bool, // .gitignore, .ignore and git excludes
+    pub excludes: Vec<String>,      // Extra gitignore-style globs, e.g. "node_modules"
+    pub max_depth: Option<usize>,
+    pub follow_symlinks: bool,
+}
+
+pub fn build_walker(root: &Path, options: &WalkOptions) -> Result<ignore::Walk, String> {
+    walk_builder(root, options).map(|builder| builder.build())
+}
+
+/// The builder behind `build_walker`, for walks that prune further with `filter_entry`.
+pub fn walk_builder(root: &Path, options: &WalkOptions) -> Result<WalkBuilder, String> {
+    let mut overrides = OverrideBuilder::new(root);
+    for pattern in options.excludes.iter().map(|p| p.trim()).filter(|p| !p.is_empty()) {
+        // A leading '!' turns an override glob into an exclusion
+        overrides
+            .add(&format!("!{}", pattern))
+            .map_err(|e| e.to_string())?;
+    }
+    let overrides = overrides.build().map_err(|e| e.to_string())?;
+
+    let respect = options.respect_ignore_files;
+    let mut builder = WalkBuilder::new(root);
+    builder
+        .hidden(false) // Hidden files are handled by SearchFilter
+        .parents(false) // Ignore files above the search root don't apply
+        .ignore(respect)
+        .git_ignore(respect)
+        .git_global(respect)
+        .git_exclude(respect)
+        .require_git(false)
+        .max_depth(options.max_depth)
+        .follow_links(options.follow_symlinks)
+        .overrides(overrides);
+    Ok(builder)
+}
+
+#[derive(Clone, Debug)]
+pub struct ContentMatch {
+    pub path: PathBuf,
+    pub line_number: usize,
+    pub snippet: String,
+    pub match_range: Range<usize>, // Byte range of the hit inside `snippet`
+}
+
+const MAX_CONTENT_MATCHES: usize = 5000;
+const MAX_SNIPPET_LEN: usize = 200;
+const BINARY_SNIFF_LEN: usize = 8192;
+const SEARCH_BATCH_SIZE: usize = 256;
+const SEARCH_FLUSH_INTERVAL: Duration = Duration::from_millis(100);
+
+// Collects search hits and hands them out in batches, either when the batch
+// fills up or when the last flush is old enough that the UI should see progress.
+struct Batcher<T, F: FnMut(Vec<T>)> {
+    batch: Vec<T>,
+    last_flush: Instant,
+    on_batch: F,
+}
+
+impl<T, F: FnMut(Vec<T>)> Batcher<T, F> {
+    fn new(on_batch: F) -> Self {
+        Self {
+            batch: Vec::new(),
+            last_flush: Instant::now(),
+            on_batch,
+        }
+    }
+
+    fn push(&mut self, item: T) {
+        self.batch.push(item);
+        if self.batch.len() >= SEARCH_BATCH_SIZE {
+            self.flush();
+        }
+    }
+
+    fn tick(&mut self) {
+        if !self.batch.is_empty() && self.last_flush.elapsed() >= SEARCH_FLUSH_INTERVAL {
+            self.flush();
+        }
+    }
+
+    fn flush(&mut self) {
+        if !self.batch.is_empty() {
+            (self.on_batch)(std::mem::take(&mut self.batch));
+        }
+        self.last_flush = Instant::now();
+    }
 }
 
 pub fn encrypt_file(path: &Path, password: &str) -> Result<(), String> {
@@ -151,77 +392,183 @@ pub fn encrypt_file(path: &Path, password: &str) -> Result<(), String> {
     
     }
 
-pub fn get_drives() -> Vec<PathBuf> {
+#[derive(Clone, Debug, PartialEq)]
+pub struct DriveInfo {
+    pub name: String,
+    pub mount_point: PathBuf,
+    pub file_system: String,
+    pub total_space: u64,
+    pub available_space: u64,
+    pub is_removable: bool,
+    pub is_read_only: bool,
+}
+
+// Kernel and virtual filesystems that Linux lists as mounts but that hold no user files
+const PSEUDO_FILESYSTEMS: &[&str] = &[
+    "autofs", "binfmt_misc", "bpf", "cgroup", "cgroup2", "configfs", "debugfs", "devpts",
+    "devtmpfs", "efivarfs", "fusectl", "hugetlbfs", "mqueue", "nsfs", "proc", "pstore",
+    "ramfs", "rpc_pipefs", "securityfs", "squashfs", "sysfs", "tmpfs", "tracefs",
+];
+
+pub fn get_drives() -> Vec<DriveInfo> {
     let disks = Disks::new_with_refreshed_list();
     disks
         .list()
         .iter()
-        .map(|disk| disk.mount_point().to_path_buf())
+        .map(|disk| DriveInfo {
+            name: disk.name().to_string_lossy().to_string(),
+            mount_point: disk.mount_point().to_path_buf(),
+            file_system: disk.file_system().to_string_lossy().to_string(),
+            total_space: disk.total_space(),
+            available_space: disk.available_space(),
+            is_removable: disk.is_removable(),
+            is_read_only: disk.is_read_only(),
+        })
+        .filter(|drive| {
+            !cfg!(target_os = "linux") || !PSEUDO_FILESYSTEMS.contains(&drive.file_system.as_str())
+        })
         .collect()
 }
 
-pub fn read_directory(path: &Path) -> Result<Vec<FileEntry>, String> {
-    let mut entries = Vec::new();
-
-    match fs::read_dir(path) {
-        Ok(read_dir) => {
-            for entry_result in read_dir {
-                if let Ok(entry) = entry_result {
-                    let path = entry.path();
-                    let metadata = match entry.metadata() {
-                        Ok(m) => m,
-                        Err(_) => continue, // Skip files we can't stat
-                    };
-
-                    let name = entry.file_name().to_string_lossy().to_string();
-
-                    // Windows specific hidden check
-                    let is_hidden = (metadata.file_attributes() & 0x2) != 0;
-
-                    let file_type = if metadata.is_dir() {
-                        FileType::Directory
-                    } else if metadata.is_symlink() {
-                        FileType::Symlink
-                    } else {
-                        FileType::File
-                    };
-
-                    let size = if metadata.is_dir() { 0 } else { metadata.len() };
-
-                    let modified = metadata
-                        .modified()
-                        .unwrap_or(SystemTime::now())
-                        .duration_since(SystemTime::UNIX_EPOCH)
-                        .unwrap_or_default()
-                        .as_secs() as i64;
-
-                    entries.push(FileEntry {
-                        name,
-                        path,
-                        file_type,
-                        size,
-                        modified,
-                        is_hidden,
-                    });
-                }
-            }
-        }
-        Err(e) => return Err(e.to_string()),
+// Windows specific hidden check
+fn is_hidden(metadata: &fs::Metadata) -> bool {
+    (metadata.file_attributes() & 0x2) != 0
+}
+
+pub fn file_entry_from_metadata(name: String, path: PathBuf, metadata: &fs::Metadata) -> FileEntry {
+    let is_hidden = is_hidden(metadata);
+
+    let file_type = if metadata.is_dir() {
+        FileType::Directory
+    } else if metadata.is_symlink() {
+        FileType::Symlink
+    } else {
+        FileType::File
+    };
+
+    let size = if metadata.is_dir() { 0 } else { metadata.len() };
+    let category = if file_type == FileType::File {
+        file_category(&path)
+    } else {
+        FileCategory::Other
+    };
+
+    let modified = metadata
+        .modified()
+        .unwrap_or(SystemTime::now())
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs() as i64;
+
+    FileEntry {
+        name,
+        path,
+        file_type,
+        size,
+        modified,
+        is_hidden,
+        category,
     }
+}
 
-    // Sort: Directories first, then files. Alphabetical within groups.
-    entries.sort_by(|a, b| {
-        match (
-            a.file_type == FileType::Directory,
-            b.file_type == FileType::Directory,
-        ) {
-            (true, false) => std::cmp::Ordering::Less,
-            (false, true) => std::cmp::Ordering::Greater,
-            _ => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+pub enum ListingEvent {
+    Entries(Vec<FileEntry>),  // Names, types and hidden flags only, size/modified unset
+    Listed,                   // Every name has been sent
+    Metadata(Vec<FileEntry>), // Complete entries for paths already sent
+}
+
+/// Modification time of a directory, used to tell if a listing is stale. Kept at full
+/// precision: two changes within the same second must not look like one.
+pub fn dir_mtime(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+// Lists `path` in two passes so huge folders show up quickly: names and types
+// first (no stat needed on most platforms), then the full metadata in batches.
+// Returns early, without `Listed`, once `cancel` is set.
+pub fn stream_directory(
+    path: &Path,
+    cancel: &AtomicBool,
+    mut on_event: impl FnMut(ListingEvent),
+) -> Result<(), String> {
+    let read_dir = fs::read_dir(path).map_err(|e| e.to_string())?;
+    let mut dir_entries = Vec::new();
+
+    let mut names = Batcher::new(|batch| on_event(ListingEvent::Entries(batch)));
+    for entry in read_dir.flatten() {
+        if cancel.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+        let file_type = match entry.file_type() {
+            Ok(t) if t.is_dir() => FileType::Directory,
+            Ok(t) if t.is_symlink() => FileType::Symlink,
+            Ok(_) => FileType::File,
+            Err(_) => FileType::Unknown,
+        };
+        let path = entry.path();
+        let category = if file_type == FileType::File {
+            file_category(&path)
+        } else {
+            FileCategory::Other
+        };
+        // Windows hands out the attributes with the listing, so this costs no stat and
+        // hidden entries never show up before their metadata does
+        let is_hidden = entry.metadata().is_ok_and(|m| is_hidden(&m));
+        names.push(FileEntry {
+            name: entry.file_name().to_string_lossy().to_string(),
+            category,
+            path,
+            file_type,
+            size: 0,
+            modified: 0,
+            is_hidden,
+        });
+        names.tick();
+        dir_entries.push(entry);
+    }
+    names.flush();
+    drop(names);
+    on_event(ListingEvent::Listed);
+
+    let mut details = Batcher::new(|batch| on_event(ListingEvent::Metadata(batch)));
+    for entry in dir_entries {
+        if cancel.load(Ordering::Relaxed) {
+            return Ok(());
         }
-    });
+        // Entries we can't stat keep their placeholder metadata
+        if let Ok(metadata) = entry.metadata() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            details.push(file_entry_from_metadata(name, entry.path(), &metadata));
+        }
+        details.tick();
+    }
+    details.flush();
+    Ok(())
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FolderSize {
+    pub bytes: u64,
+    pub files: u64,
+    pub dirs: u64,
+}
 
-    Ok(entries)
+/// Totals everything below `path`, not following symlinks and skipping unreadable
+/// entries. Returns None if cancelled.
+pub fn folder_size(path: &Path, cancel: &AtomicBool) -> Option<FolderSize> {
+    let mut total = FolderSize::default();
+    for entry in WalkDir::new(path).min_depth(1).into_iter().flatten() {
+        if cancel.load(Ordering::Relaxed) {
+            return None;
+        }
+        if entry.file_type().is_dir() {
+            total.dirs += 1;
+        } else {
+            total.files += 1;
+            total.bytes += entry.metadata().map(|m| m.len()).unwrap_or(0);
+        }
+    }
+    Some(total)
 }
 
 pub fn delete_entry(path: &Path) -> Result<(), String> {
@@ -232,6 +579,10 @@ pub fn delete_entry(path: &Path) -> Result<(), String> {
     }
 }
 
+pub fn trash_entry(path: &Path) -> Result<(), String> {
+    trash::delete(path).map_err(|e| e.to_string())
+}
+
 pub fn rename_entry(old_path: &Path, new_name: &str) -> Result<(), String> {
     let parent = old_path.parent().ok_or("No parent directory")?;
     let new_path = parent.join(new_name);
@@ -286,45 +637,163 @@ pub fn create_file(parent: &Path, name: &str) -> Result<(), String> {
     Ok(())
 }
 
-pub fn search_directory_recursive(root: &Path, query: &str) -> Vec<FileEntry> {
-    let mut results = Vec::new();
+pub fn search_directory_recursive(
+    root: &Path,
+    query: &str,
+    filter: &SearchFilter,
+    options: &WalkOptions,
+    cancel: &AtomicBool,
+    on_batch: impl FnMut(Vec<FileEntry>),
+) -> Result<(), String> {
+    let mut results = Batcher::new(on_batch);
     let query_lower = query.to_lowercase();
 
-    for entry in WalkDir::new(root).into_iter().filter_map(|e| e.ok()) {
+    for entry in build_walker(root, options)?.filter_map(|e| e.ok()) {
+        if cancel.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+        results.tick();
+        // The root itself isn't a result, which matters for filter-only searches
+        if entry.depth() == 0 {
+            continue;
+        }
+
         let name = entry.file_name().to_string_lossy().to_string();
         if name.to_lowercase().contains(&query_lower) {
             let path = entry.path().to_path_buf();
-            
+
             if let Ok(metadata) = entry.metadata() {
-                 let is_hidden = (metadata.file_attributes() & 0x2) != 0;
-                 let file_type = if metadata.is_dir() {
-                    FileType::Directory
-                } else if metadata.is_symlink() {
-                    FileType::Symlink
-                } else {
-                    FileType::File
-                };
-                let size = if metadata.is_dir() { 0 } else { metadata.len() };
-                
-                let modified = metadata
-                    .modified()
-                    .unwrap_or(SystemTime::now())
-                    .duration_since(SystemTime::UNIX_EPOCH)
-                    .unwrap_or_default()
-                    .as_secs() as i64;
-
-                results.push(FileEntry {
-                    name,
-                    path,
-                    file_type,
-                    size,
-                    modified,
-                    is_hidden,
+                let entry = file_entry_from_metadata(name, path, &metadata);
+                if filter.matches(&entry, &metadata) {
+                    results.push(entry);
+                }
+            }
+        }
+    }
+    results.flush();
+    Ok(())
+}
+
+/// Lists paths under `root` (excluding the root) as `(path, is_dir)`, stopping after `limit`.
+pub fn collect_paths(
+    root: &Path,
+    options: &WalkOptions,
+    limit: usize,
+) -> Result<Vec<(PathBuf, bool)>, String> {
+    Ok(build_walker(root, options)?
+        .filter_map(|e| e.ok())
+        .filter(|e| e.depth() > 0)
+        .take(limit)
+        .map(|e| {
+            let is_dir = e.file_type().is_some_and(|t| t.is_dir());
+            (e.into_path(), is_dir)
+        })
+        .collect())
+}
+
+pub fn build_content_matcher(query: &str, use_regex: bool) -> Result<Regex, String> {
+    let pattern = if use_regex {
+        query.to_string()
+    } else {
+        regex::escape(query)
+    };
+    RegexBuilder::new(&pattern)
+        .case_insensitive(true)
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+// Treat anything with a NUL byte near the start as binary, like grep does.
+fn is_binary_file(path: &Path) -> bool {
+    let mut buf = [0u8; BINARY_SNIFF_LEN];
+    match fs::File::open(path).and_then(|mut f| f.read(&mut buf)) {
+        Ok(n) => buf[..n].contains(&0),
+        Err(_) => true,
+    }
+}
+
+fn make_snippet(line: &str, range: Range<usize>) -> (String, Range<usize>) {
+    let trimmed = line.trim_start();
+    let offset = line.len() - trimmed.len();
+    let trimmed = trimmed.trim_end();
+    let mut end = range.end.saturating_sub(offset).min(trimmed.len());
+    let mut start = range.start.saturating_sub(offset).min(end);
+
+    if trimmed.len() <= MAX_SNIPPET_LEN {
+        return (trimmed.to_string(), start..end);
+    }
+
+    // Keep a window of context around the hit on char boundaries
+    let mut from = start.saturating_sub(MAX_SNIPPET_LEN / 4);
+    while !trimmed.is_char_boundary(from) {
+        from -= 1;
+    }
+    let mut to = (from + MAX_SNIPPET_LEN).max(end).min(trimmed.len());
+    while !trimmed.is_char_boundary(to) {
+        to += 1;
+    }
+    start -= from;
+    end -= from;
+    (trimmed[from..to].to_string(), start..end)
+}
+
+pub fn search_file_contents(
+    root: &Path,
+    matcher: &Regex,
+    options: &WalkOptions,
+    cancel: &AtomicBool,
+    on_batch: impl FnMut(Vec<ContentMatch>),
+) -> Result<(), String> {
+    let mut results = Batcher::new(on_batch);
+    let mut total = 0;
+
+    for entry in build_walker(root, options)?.filter_map(|e| e.ok()) {
+        if cancel.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+        results.tick();
+        let is_file = entry.file_type().is_some_and(|t| t.is_file());
+        if !is_file || is_binary_file(entry.path()) {
+            continue;
+        }
+
+        let file = match fs::File::open(entry.path()) {
+            Ok(f) => f,
+            Err(_) => continue,
+        };
+        let mut reader = BufReader::new(file);
+        let mut buf = Vec::new();
+        let mut line_number = 0;
+
+        // read_until + lossy decoding so one bad byte doesn't abort the whole file
+        while let Ok(n) = reader.read_until(b'\n', &mut buf) {
+            if n == 0 {
+                break;
+            }
+            if cancel.load(Ordering::Relaxed) {
+                return Ok(());
+            }
+            line_number += 1;
+            let line = String::from_utf8_lossy(&buf);
+            if let Some(m) = matcher.find(&line) {
+                let (snippet, match_range) = make_snippet(&line, m.range());
+                results.push(ContentMatch {
+                    path: entry.path().to_path_buf(),
+                    line_number,
+                    snippet,
+                    match_range,
                 });
+                total += 1;
+                if total >= MAX_CONTENT_MATCHES {
+                    results.flush();
+                    return Ok(());
+                }
             }
+            buf.clear();
         }
     }
-    results
+    results.flush();
+    Ok(())
 }
 
 pub fn create_zip(src_path: &Path, dest_path: &Path) -> Result<(), String> {
@@ -0,0 +1,168 @@
+use memchr::memmem;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+pub const BYTES_PER_ROW: usize = 16;
+const FIND_CHUNK: usize = 4 * 1024 * 1024;
+
+// A file open in the hex viewer. Only the rows in view are read, so any size opens at
+// once; nothing is mapped, so a file truncated meanwhile only reads short.
+pub struct HexFile {
+    path: PathBuf,
+    file: File,
+    len: usize,
+}
+
+impl HexFile {
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let file = File::open(path).map_err(|e| e.to_string())?;
+        let len = file.metadata().map_err(|e| e.to_string())?.len() as usize;
+        Ok(Self {
+            path: path.to_path_buf(),
+            file,
+            len,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn rows(&self) -> usize {
+        self.len.div_ceil(BYTES_PER_ROW)
+    }
+
+    /// Up to `len` bytes from `offset`, fewer at the end of the file.
+    pub fn read(&self, offset: usize, len: usize) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(len.min(self.len));
+        let mut file = &self.file;
+        if file.seek(SeekFrom::Start(offset as u64)).is_ok() {
+            let _ = file.take(len as u64).read_to_end(&mut bytes);
+        }
+        bytes
+    }
+}
+
+/// Next occurrence of `pattern` in the file after `from`, wrapping around to the start.
+/// Reads a chunk at a time, so it belongs on a background thread; None once `cancel` is set.
+pub fn find(
+    path: &Path,
+    pattern: &[u8],
+    from: usize,
+    cancel: &AtomicBool,
+) -> Result<Option<usize>, String> {
+    let mut file = File::open(path).map_err(|e| e.to_string())?;
+    let len = file.metadata().map_err(|e| e.to_string())?.len() as usize;
+    let start = (from + 1).min(len);
+    if let Some(found) = find_between(&mut file, pattern, start, len, cancel)? {
+        return Ok(Some(found));
+    }
+    // Matches may start anywhere before `start`, so the end overlaps it by the pattern
+    let end = (start + pattern.len().saturating_sub(1)).min(len);
+    find_between(&mut file, pattern, 0, end, cancel)
+}
+
+fn find_between(
+    file: &mut File,
+    pattern: &[u8],
+    start: usize,
+    end: usize,
+    cancel: &AtomicBool,
+) -> Result<Option<usize>, String> {
+    let finder = memmem::Finder::new(pattern);
+    let mut chunk = Vec::new();
+    let mut pos = start;
+    while pos < end {
+        if cancel.load(Ordering::Relaxed) {
+            return Ok(None);
+        }
+        let wanted = FIND_CHUNK.max(pattern.len()).min(end - pos);
+        chunk.clear();
+        file.seek(SeekFrom::Start(pos as u64)).map_err(|e| e.to_string())?;
+        (&mut *file)
+            .take(wanted as u64)
+            .read_to_end(&mut chunk)
+            .map_err(|e| e.to_string())?;
+        if let Some(i) = finder.find(&chunk) {
+            return Ok(Some(pos + i));
+        }
+        if chunk.len() < wanted || pos + chunk.len() >= end {
+            break;
+        }
+        // Chunks overlap so a match across the seam is still found
+        pos += chunk.len() + 1 - pattern.len().max(1);
+    }
+    Ok(None)
+}
+
+/// Parses a search pattern: text in double quotes, otherwise hex bytes with optional
+/// spaces ("DEADBEEF", "de ad be ef", "0x7f 45 4c 46").
+pub fn parse_pattern(input: &str) -> Result<Vec<u8>, String> {
+    let input = input.trim();
+    let usage = || "Enter whole hex bytes, or \"text\" in quotes".to_string();
+    if let Some(text) = input.strip_prefix('"') {
+        let text = text.strip_suffix('"').unwrap_or(text);
+        return if text.is_empty() { Err(usage()) } else { Ok(text.as_bytes().to_vec()) };
+    }
+
+    let digits: String = input
+        .split_whitespace()
+        .map(|part| part.trim_start_matches("0x").trim_start_matches("0X"))
+        .collect();
+    // Checked up front, so the pairs below are ASCII and slice on char boundaries
+    if let Some(bad) = digits.chars().find(|c| !c.is_ascii_hexdigit()) {
+        return Err(format!("Not a hex byte: {}", bad));
+    }
+    if digits.is_empty() || digits.len() % 2 != 0 {
+        return Err(usage());
+    }
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&digits[i..i + 2], 16).map_err(|e| e.to_string()))
+        .collect()
+}
+
+/// The bytes at the cursor read as common types, for the data inspector.
+/// Types needing more bytes than are left are skipped.
+pub fn inspect(bytes: &[u8]) -> Vec<(&'static str, String)> {
+    fn take<const N: usize>(bytes: &[u8]) -> Option<[u8; N]> {
+        bytes.get(..N)?.try_into().ok()
+    }
+
+    let mut rows = Vec::new();
+    if let Some([b]) = take::<1>(bytes) {
+        rows.push(("u8", b.to_string()));
+        rows.push(("i8", (b as i8).to_string()));
+        rows.push(("Binary", format!("{:08b}", b)));
+    }
+    if let Some(b) = take::<2>(bytes) {
+        rows.push(("u16 LE", u16::from_le_bytes(b).to_string()));
+        rows.push(("u16 BE", u16::from_be_bytes(b).to_string()));
+        rows.push(("i16 LE", i16::from_le_bytes(b).to_string()));
+    }
+    if let Some(b) = take::<4>(bytes) {
+        rows.push(("u32 LE", u32::from_le_bytes(b).to_string()));
+        rows.push(("u32 BE", u32::from_be_bytes(b).to_string()));
+        rows.push(("i32 LE", i32::from_le_bytes(b).to_string()));
+        rows.push(("f32 LE", f32::from_le_bytes(b).to_string()));
+    }
+    if let Some(b) = take::<8>(bytes) {
+        rows.push(("u64 LE", u64::from_le_bytes(b).to_string()));
+        rows.push(("i64 LE", i64::from_le_bytes(b).to_string()));
+        rows.push(("f64 LE", f64::from_le_bytes(b).to_string()));
+    }
+    // Shortest prefix that forms a character
+    if let Some(c) = (1..=bytes.len().min(4))
+        .find_map(|n| std::str::from_utf8(&bytes[..n]).ok())
+        .and_then(|s| s.chars().next())
+    {
+        rows.push(("UTF-8", format!("{:?} U+{:04X}", c, c as u32)));
+    }
+    rows
+}
@@ -0,0 +1,219 @@
+use eframe::egui::text::{LayoutJob, TextFormat};
+use eframe::egui::{Color32, FontId, Stroke};
+use std::cell::RefCell;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+use syntect::highlighting::{
+    Color, FontStyle, HighlightIterator, HighlightState, Highlighter as ThemeHighlighter, Style,
+    ThemeSet,
+};
+use syntect::parsing::{ParseState, ScopeStack, SyntaxReference, SyntaxSet};
+use syntect::util::LinesWithEndings;
+
+// Syntax highlighting for the text preview, built on syntect's bundled syntaxes and themes.
+//
+// The editor re-lays out its text every frame and after every keystroke, so the parser
+// state at the start of each line is kept. An edit re-highlights from the first changed
+// line and stops as soon as it reaches an unchanged line in the same state as before,
+// usually the line after the edit.
+pub struct Highlighter {
+    syntaxes: SyntaxSet,
+    themes: ThemeSet,
+    cache: RefCell<Option<Highlighted>>,
+}
+
+// The last text laid out, line by line
+struct Highlighted {
+    key: u64, // Path, syntax, theme and font
+    lines: Vec<HighlightedLine>,
+    job: LayoutJob,
+}
+
+#[derive(Clone)]
+struct HighlightedLine {
+    start: (ParseState, HighlightState), // Parser state before the line
+    len: usize,                          // In bytes, newline included
+    styles: Vec<(Style, usize)>,         // Runs of the line, by length in bytes
+}
+
+impl Highlighter {
+    pub fn new() -> Self {
+        Self {
+            syntaxes: SyntaxSet::load_defaults_newlines(),
+            themes: ThemeSet::load_defaults(),
+            cache: RefCell::new(None),
+        }
+    }
+
+    // By extension (or file name, for `Makefile` and friends), then by a shebang or
+    // modeline on the first line
+    fn syntax_for(&self, path: &Path, text: &str) -> &SyntaxReference {
+        let by_name = |name: Option<&std::ffi::OsStr>| {
+            name.and_then(|n| n.to_str())
+                .and_then(|n| self.syntaxes.find_syntax_by_extension(n))
+        };
+        by_name(path.extension())
+            .or_else(|| by_name(path.file_name()))
+            .or_else(|| {
+                let first_line = text.lines().next().unwrap_or_default();
+                self.syntaxes.find_syntax_by_first_line(first_line)
+            })
+            .unwrap_or_else(|| self.syntaxes.find_syntax_plain_text())
+    }
+
+    /// Lays out `text` coloured for the file at `path`, using the named syntect theme.
+    /// Lines are not wrapped so they stay aligned with the line number gutter.
+    pub fn layout_job(&self, text: &str, path: &Path, theme: &str, font_id: FontId) -> LayoutJob {
+        let syntax = self.syntax_for(path, text);
+        let mut hasher = DefaultHasher::new();
+        (path, &syntax.name, theme, &font_id).hash(&mut hasher);
+        let key = hasher.finish();
+
+        let mut slot = self.cache.borrow_mut();
+        let mut cache = match slot.take() {
+            Some(cache) if cache.key == key => cache,
+            _ => Highlighted {
+                key,
+                lines: Vec::new(),
+                job: LayoutJob::default(),
+            },
+        };
+        if cache.job.text == text && !cache.lines.is_empty() {
+            let job = cache.job.clone();
+            *slot = Some(cache);
+            return job;
+        }
+
+        let theme = self
+            .themes
+            .themes
+            .get(theme)
+            .unwrap_or_else(|| &self.themes.themes["base16-ocean.dark"]);
+        let theme_highlighter = ThemeHighlighter::new(theme);
+
+        // What the edit left alone at either end
+        let old = &cache.job.text;
+        let prefix = old.bytes().zip(text.bytes()).take_while(|(a, b)| a == b).count();
+        let suffix = old
+            .bytes()
+            .rev()
+            .zip(text.bytes().rev())
+            .take(old.len().min(text.len()) - prefix)
+            .take_while(|(a, b)| a == b)
+            .count();
+
+        // Lines before the edit stay as they are. The last of them is redone when all
+        // lines are kept, since only the state before a line is stored.
+        let mut kept = 0;
+        let mut offset = 0;
+        for line in &cache.lines {
+            let end = offset + line.len;
+            if end > prefix || !old[..end].ends_with('\n') || kept + 1 == cache.lines.len() {
+                break;
+            }
+            kept += 1;
+            offset = end;
+        }
+        let tail = cache.lines.split_off(kept);
+        let tail_starts: Vec<usize> = tail
+            .iter()
+            .scan(offset, |start, line| {
+                let this = *start;
+                *start += line.len;
+                Some(this)
+            })
+            .collect();
+
+        let (mut parse, mut highlight) = match tail.first() {
+            Some(line) => line.start.clone(),
+            None => (
+                ParseState::new(syntax),
+                HighlightState::new(&theme_highlighter, ScopeStack::new()),
+            ),
+        };
+        let mut failed = false;
+        for (start, line) in line_offsets(&text[offset..]) {
+            let start = offset + start;
+            // Past the edit, a line the old text had in the same state ends the work:
+            // everything after it comes out as before
+            if start >= text.len() - suffix {
+                let old_start = start + old.len() - text.len();
+                if let Ok(i) = tail_starts.binary_search(&old_start) {
+                    if tail[i].start.0 == parse && tail[i].start.1 == highlight {
+                        cache.lines.extend_from_slice(&tail[i..]);
+                        break;
+                    }
+                }
+            }
+
+            let state = (parse.clone(), highlight.clone());
+            let ops = if failed {
+                None
+            } else {
+                parse.parse_line(line, &self.syntaxes).ok()
+            };
+            let styles = match ops {
+                Some(ops) => HighlightIterator::new(&mut highlight, &ops, line, &theme_highlighter)
+                    .map(|(style, piece)| (style, piece.len()))
+                    .collect(),
+                None => {
+                    // Keep the rest of the file readable, just uncoloured
+                    failed = true;
+                    vec![(PLAIN, line.len())]
+                }
+            };
+            cache.lines.push(HighlightedLine {
+                start: state,
+                len: line.len(),
+                styles,
+            });
+        }
+
+        let mut job = LayoutJob::default();
+        job.wrap.max_width = f32::INFINITY;
+        let mut offset = 0;
+        for (style, len) in cache.lines.iter().flat_map(|line| &line.styles) {
+            job.append(&text[offset..offset + len], 0.0, text_format(*style, font_id.clone()));
+            offset += len;
+        }
+
+        cache.job = job.clone();
+        *slot = Some(cache);
+        job
+    }
+}
+
+const PLAIN: Style = Style {
+    foreground: Color {
+        r: 160,
+        g: 160,
+        b: 160,
+        a: 255,
+    },
+    background: Color::BLACK,
+    font_style: FontStyle::empty(),
+};
+
+fn line_offsets(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    LinesWithEndings::from(text).scan(0, |offset, line| {
+        let start = *offset;
+        *offset += line.len();
+        Some((start, line))
+    })
+}
+
+fn text_format(style: Style, font_id: FontId) -> TextFormat {
+    let color = Color32::from_rgb(style.foreground.r, style.foreground.g, style.foreground.b);
+    TextFormat {
+        font_id,
+        color,
+        italics: style.font_style.contains(FontStyle::ITALIC),
+        underline: if style.font_style.contains(FontStyle::UNDERLINE) {
+            Stroke::new(1.0, color)
+        } else {
+            Stroke::NONE
+        },
+        ..Default::default()
+    }
+}
@@ -0,0 +1,175 @@
+use exif::{Exif, In, Reader, Tag, Value};
+use image::{ColorType, ImageDecoder, ImageReader};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+// How a photo has to be turned to appear upright, from its EXIF orientation (1 to 8)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Orientation(u32);
+
+impl Orientation {
+    pub const UPRIGHT: Orientation = Orientation(1);
+
+    /// Mirrored left to right before rotating (orientations 2, 4, 5 and 7).
+    pub fn mirrored(&self) -> bool {
+        matches!(self.0, 2 | 4 | 5 | 7)
+    }
+
+    /// Clockwise rotation in degrees, applied after any mirroring.
+    pub fn degrees(&self) -> u32 {
+        match self.0 {
+            3 | 4 => 180,
+            6 | 7 => 90,
+            5 | 8 => 270,
+            _ => 0,
+        }
+    }
+
+    /// True if width and height trade places once upright.
+    pub fn swaps_axes(&self) -> bool {
+        self.degrees() % 180 != 0
+    }
+
+    pub fn label(&self) -> String {
+        match (self.degrees(), self.mirrored()) {
+            (0, false) => "Normal".to_string(),
+            (0, true) => "Mirrored".to_string(),
+            (degrees, false) => format!("Rotated {}° CW", degrees),
+            (degrees, true) => format!("Mirrored, rotated {}° CW", degrees),
+        }
+    }
+}
+
+// What the image preview shows besides the pixels. Every part is optional: formats
+// the decoder doesn't know can still carry EXIF, and most files carry none.
+pub struct ImageMeta {
+    pub dimensions: Option<(u32, u32)>, // As stored, before orientation
+    pub format: Option<String>,
+    pub color: Option<String>,
+    pub orientation: Orientation,
+    pub exif: Vec<(&'static str, String)>,
+    pub gps: Option<(f64, f64)>, // Latitude and longitude in degrees
+}
+
+impl ImageMeta {
+    pub fn read(path: &Path) -> Self {
+        let mut meta = Self {
+            dimensions: None,
+            format: None,
+            color: None,
+            orientation: Orientation::UPRIGHT,
+            exif: Vec::new(),
+            gps: None,
+        };
+
+        // Only the header is decoded
+        if let Ok(reader) = ImageReader::open(path).and_then(|r| r.with_guessed_format()) {
+            meta.format = reader.format().map(|f| format!("{:?}", f).to_uppercase());
+            if let Ok(decoder) = reader.into_decoder() {
+                meta.dimensions = Some(decoder.dimensions());
+                meta.color = Some(color_label(decoder.color_type()));
+            }
+        }
+
+        let exif = File::open(path)
+            .ok()
+            .and_then(|file| Reader::new().read_from_container(&mut BufReader::new(file)).ok());
+        if let Some(exif) = exif {
+            meta.read_exif(&exif);
+        }
+        meta
+    }
+
+    /// Width and height as displayed, after orientation.
+    pub fn upright_size(&self) -> Option<(u32, u32)> {
+        let (width, height) = self.dimensions?;
+        Some(if self.orientation.swaps_axes() { (height, width) } else { (width, height) })
+    }
+
+    /// Label/value rows for the info panel, GPS aside.
+    pub fn summary(&self) -> Vec<(&'static str, String)> {
+        let mut rows = Vec::new();
+        if let Some((width, height)) = self.upright_size() {
+            rows.push(("Dimensions", format!("{} × {}", width, height)));
+        }
+        if let Some(format) = &self.format {
+            rows.push(("Format", format.clone()));
+        }
+        if let Some(color) = &self.color {
+            rows.push(("Colour", color.clone()));
+        }
+        if self.orientation != Orientation::UPRIGHT {
+            rows.push(("Orientation", self.orientation.label()));
+        }
+        rows.extend(self.exif.iter().cloned());
+        rows
+    }
+
+    fn read_exif(&mut self, exif: &Exif) {
+        let text = |tag| {
+            exif.get_field(tag, In::PRIMARY)
+                .map(|field| field.display_value().with_unit(exif).to_string())
+                .map(|value| value.trim_matches('"').trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+
+        if let Some(value) = exif
+            .get_field(Tag::Orientation, In::PRIMARY)
+            .and_then(|field| field.value.get_uint(0))
+            .filter(|value| (1..=8).contains(value))
+        {
+            self.orientation = Orientation(value);
+        }
+
+        // Many cameras repeat the make at the start of the model
+        let camera = match (text(Tag::Make), text(Tag::Model)) {
+            (Some(make), Some(model)) if model.starts_with(&make) => Some(model),
+            (Some(make), Some(model)) => Some(format!("{} {}", make, model)),
+            (make, model) => make.or(model),
+        };
+        let rows = [
+            ("Camera", camera),
+            ("Lens", text(Tag::LensModel)),
+            ("Date Taken", text(Tag::DateTimeOriginal).or_else(|| text(Tag::DateTime))),
+            ("Exposure", text(Tag::ExposureTime)),
+            ("Aperture", text(Tag::FNumber)),
+            ("ISO", text(Tag::PhotographicSensitivity)),
+            ("Focal Length", text(Tag::FocalLength)),
+        ];
+        self.exif = rows
+            .into_iter()
+            .filter_map(|(label, value)| Some((label, value?)))
+            .collect();
+
+        let coordinate = |tag, ref_tag, negative: &[u8]| {
+            let Value::Rational(parts) = &exif.get_field(tag, In::PRIMARY)?.value else {
+                return None;
+            };
+            let degrees = parts
+                .iter()
+                .zip([1.0, 60.0, 3600.0])
+                .map(|(part, divisor)| part.to_f64() / divisor)
+                .sum::<f64>();
+            let sign = match &exif.get_field(ref_tag, In::PRIMARY)?.value {
+                Value::Ascii(refs) if refs.first().is_some_and(|r| r.starts_with(negative)) => -1.0,
+                _ => 1.0,
+            };
+            Some(sign * degrees).filter(|value| value.is_finite())
+        };
+        self.gps = coordinate(Tag::GPSLatitude, Tag::GPSLatitudeRef, b"S")
+            .zip(coordinate(Tag::GPSLongitude, Tag::GPSLongitudeRef, b"W"));
+    }
+}
+
+fn color_label(color: ColorType) -> String {
+    let channels = match (color.has_color(), color.has_alpha()) {
+        (true, true) => "RGBA",
+        (true, false) => "RGB",
+        (false, true) => "Grayscale + alpha",
+        (false, false) => "Grayscale",
+    };
+    let bits = color.bits_per_pixel() / u16::from(color.channel_count());
+    let float = matches!(color, ColorType::Rgb32F | ColorType::Rgba32F);
+    format!("{}, {}-bit{}", channels, bits, if float { " float" } else { "" })
+}
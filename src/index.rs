@@ -0,0 +1,352 @@
+use crate::filesystem::{file_entry_from_metadata, walk_builder, FileEntry, FileType, WalkOptions};
+use chrono::Local;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use notify::{EventKind, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+const INDEX_FILENAME: &str = "rust_explorer_index.json";
+// Full rescan period when the roots can't be watched
+const REFRESH_INTERVAL: Duration = Duration::from_secs(300);
+// Watcher events are applied once they go quiet for a second, or after this at the latest
+const SETTLE_MAX: Duration = Duration::from_secs(10);
+// The index is written out at most this often
+const SAVE_INTERVAL: Duration = Duration::from_secs(300);
+
+#[derive(Serialize, Deserialize)]
+struct IndexedDir {
+    mtime: Option<SystemTime>,
+    entries: Vec<FileEntry>,
+}
+
+// Filename/metadata index for a set of roots, persisted between runs.
+//
+// The roots are walked with the same rules as recursive searches (excludes, ignore
+// files, symlinks), so the index holds exactly what a walk would find; max depth is
+// left to the query since it is relative to the folder searched.
+//
+// A full scan happens once per run and keeps directories whose mtime hasn't changed
+// without stat'ing their children. After that the roots are watched and only the
+// folders the watcher reports are rescanned. Directories are shared between snapshots,
+// so handing one to the UI costs a pointer copy.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct FileIndex {
+    roots: Vec<PathBuf>,
+    #[serde(default)]
+    options: WalkOptions, // Walk rules the index was built with, max depth aside
+    dirs: Arc<HashMap<PathBuf, Arc<IndexedDir>>>,
+    pub updated: i64, // Unix seconds of the last scan that changed anything, 0 if never
+}
+
+// A directory seen by a scan: the indexed one as is, or rebuilt from its children
+enum Scanned {
+    Kept(Arc<IndexedDir>),
+    Fresh(IndexedDir),
+}
+
+impl FileIndex {
+    fn load(roots: &[PathBuf], options: &WalkOptions) -> Self {
+        let options = WalkOptions {
+            max_depth: None,
+            ..options.clone()
+        };
+        if let Ok(content) = fs::read_to_string(INDEX_FILENAME) {
+            if let Ok(index) = serde_json::from_str::<FileIndex>(&content) {
+                if index.roots == roots && index.options == options {
+                    return index;
+                }
+            }
+        }
+        Self {
+            roots: roots.to_vec(),
+            options,
+            ..Default::default()
+        }
+    }
+
+    // Written aside and renamed over, so a crash mid-write can't leave half an index
+    fn save(&self) {
+        let Ok(content) = serde_json::to_string(self) else {
+            return;
+        };
+        let temp = format!("{}.tmp", INDEX_FILENAME);
+        if fs::write(&temp, content).is_ok() && fs::rename(&temp, INDEX_FILENAME).is_err() {
+            let _ = fs::remove_file(&temp);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.dirs.values().map(|d| d.entries.len()).sum()
+    }
+
+    pub fn covers(&self, path: &Path) -> bool {
+        self.updated > 0 && self.roots.iter().any(|root| path.starts_with(root))
+    }
+
+    /// Rescans the roots, or with `dirty` only those folders (and any new ones found
+    /// in them). Returns whether anything changed, None if stopped early.
+    fn scan(&mut self, dirty: Option<&HashSet<PathBuf>>, stop: &AtomicBool) -> Option<bool> {
+        let old = self.dirs.clone();
+        let mut scanned: HashMap<PathBuf, Scanned> = HashMap::new();
+
+        // A root inside another is covered by the outer walk
+        let roots = self.roots.iter().enumerate().filter(|&(i, root)| {
+            !self.roots.iter().enumerate().any(|(j, other)| {
+                (root != other && root.starts_with(other)) || (root == other && j < i)
+            })
+        });
+        for (_, root) in roots {
+            if dirty.is_some_and(|dirty| !dirty.iter().any(|d| d.starts_with(root))) {
+                continue;
+            }
+            let Ok(mut builder) = walk_builder(root, &self.options) else {
+                continue;
+            };
+            if let Some(dirty) = dirty {
+                // Only the way down to each dirty folder, its children, and whatever is
+                // inside folders the index doesn't know yet
+                let dirty = dirty.clone();
+                let known = old.clone();
+                builder.filter_entry(move |entry| {
+                    let path = entry.path();
+                    dirty.iter().any(|d| d.starts_with(path))
+                        || path
+                            .parent()
+                            .is_some_and(|p| dirty.contains(p) || !known.contains_key(p))
+                });
+            }
+
+            for entry in builder.build().filter_map(|e| e.ok()) {
+                if stop.load(Ordering::Relaxed) {
+                    return None;
+                }
+                let path = entry.path();
+                let is_dir = entry.file_type().is_some_and(|t| t.is_dir());
+                let parent = path.parent().filter(|_| entry.depth() > 0);
+                let into_parent =
+                    matches!(parent.and_then(|p| scanned.get(p)), Some(Scanned::Fresh(_)));
+                let rescan = is_dir
+                    && dirty.is_none_or(|dirty| dirty.contains(path) || !old.contains_key(path));
+                if !into_parent && !rescan {
+                    continue;
+                }
+                let Ok(metadata) = entry.metadata() else {
+                    continue;
+                };
+
+                if rescan {
+                    let mtime = metadata.modified().ok();
+                    // On a full scan an unchanged mtime means an unchanged listing; a
+                    // dirty folder is rebuilt regardless, its files may have changed
+                    let kept = old
+                        .get(path)
+                        .filter(|d| dirty.is_none() && mtime.is_some() && d.mtime == mtime);
+                    let dir = match kept {
+                        Some(dir) => Scanned::Kept(dir.clone()),
+                        None => Scanned::Fresh(IndexedDir {
+                            mtime,
+                            entries: Vec::new(),
+                        }),
+                    };
+                    scanned.insert(path.to_path_buf(), dir);
+                }
+                if let Some(Scanned::Fresh(dir)) = parent.and_then(|p| scanned.get_mut(p)) {
+                    let name = entry.file_name().to_string_lossy().to_string();
+                    let file_entry = file_entry_from_metadata(name, path.to_path_buf(), &metadata);
+                    dir.entries.push(file_entry);
+                }
+            }
+        }
+
+        let mut changed = false;
+        let mut dirs = match dirty {
+            Some(dirty) => {
+                let mut dirs = (*old).clone();
+                // A dirty folder the walk didn't reach is gone (or excluded), with
+                // everything below it
+                for gone in dirty.iter().filter(|d| !scanned.contains_key(*d)) {
+                    dirs.retain(|path, _| !path.starts_with(gone));
+                    changed = true;
+                }
+                dirs
+            }
+            None => {
+                changed = old.keys().any(|path| !scanned.contains_key(path));
+                HashMap::new()
+            }
+        };
+        for (path, dir) in scanned {
+            let dir = match dir {
+                Scanned::Kept(dir) => dir,
+                Scanned::Fresh(dir) => {
+                    changed = true;
+                    Arc::new(dir)
+                }
+            };
+            if let Some(previous) = old.get(&path).filter(|_| dirty.is_some()) {
+                // Subfolders that dropped out of a rescanned folder go with their contents
+                for lost in previous.entries.iter().filter(|e| {
+                    e.file_type == FileType::Directory
+                        && !dir.entries.iter().any(|n| n.path == e.path)
+                }) {
+                    dirs.retain(|p, _| !p.starts_with(&lost.path));
+                }
+            }
+            dirs.insert(path, dir);
+        }
+
+        if changed || self.updated == 0 {
+            self.dirs = Arc::new(dirs);
+            self.updated = Local::now().timestamp();
+        }
+        Some(changed)
+    }
+
+    pub fn entries_under<'a>(&'a self, under: &'a Path) -> impl Iterator<Item = &'a FileEntry> {
+        self.dirs
+            .iter()
+            .filter(move |(dir, _)| dir.starts_with(under))
+            .flat_map(|(_, dir)| dir.entries.iter())
+    }
+
+    /// Names under `under` containing `query` (case-insensitive), the same matches a
+    /// recursive walk down to `max_depth` would find. Best fuzzy scores first.
+    pub fn search(
+        &self,
+        under: &Path,
+        query: &str,
+        max_depth: Option<usize>,
+        limit: usize,
+    ) -> Vec<FileEntry> {
+        let matcher = SkimMatcherV2::default();
+        let query_lower = query.to_lowercase();
+        let within_depth = |path: &Path| {
+            max_depth.is_none_or(|max| {
+                path.strip_prefix(under).is_ok_and(|rel| rel.components().count() <= max)
+            })
+        };
+        let mut scored: Vec<(i64, &FileEntry)> = self
+            .entries_under(under)
+            .filter(|e| e.name.to_lowercase().contains(&query_lower) && within_depth(&e.path))
+            .map(|e| (matcher.fuzzy_match(&e.name, query).unwrap_or_default(), e))
+            .collect();
+
+        // Shorter names win ties, "main.rs" before "main.rs.bak"
+        scored.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.name.len().cmp(&b.1.name.len())));
+        scored.truncate(limit);
+        scored.into_iter().map(|(_, e)| e.clone()).collect()
+    }
+}
+
+/// Runs the indexer for `roots` on a background thread until `stop` is set.
+/// The persisted index is sent first (if any), then a snapshot after every scan that
+/// changed something. Snapshots carry `generation` so the app can drop those of an
+/// indexer it replaced.
+pub fn spawn_indexer(
+    roots: Vec<PathBuf>,
+    options: WalkOptions,
+    generation: u64,
+    tx: Sender<(u64, Arc<FileIndex>)>,
+    stop: Arc<AtomicBool>,
+) {
+    thread::spawn(move || {
+        let mut index = FileIndex::load(&roots, &options);
+        if index.updated > 0 {
+            let _ = tx.send((generation, Arc::new(index.clone())));
+        }
+
+        // Without a watcher the index falls back to a full rescan every REFRESH_INTERVAL
+        let (event_tx, events) = mpsc::channel();
+        let watcher = notify::recommended_watcher(move |event| {
+            let _ = event_tx.send(event);
+        })
+        .ok()
+        .and_then(|mut watcher| {
+            for root in &roots {
+                watcher.watch(root, RecursiveMode::Recursive).ok()?;
+            }
+            Some(watcher)
+        });
+
+        let mut full_scan = true;
+        let mut dirty: HashSet<PathBuf> = HashSet::new();
+        let mut unsaved = false;
+        let mut saved_at: Option<Instant> = None;
+        loop {
+            let changed = if full_scan {
+                full_scan = false;
+                dirty.clear();
+                index.scan(None, &stop)
+            } else {
+                // Events in folders the index doesn't hold (excluded, or new and
+                // reached through their parent) need no walk of their own
+                dirty.retain(|dir| index.dirs.contains_key(dir));
+                let folders = std::mem::take(&mut dirty);
+                if folders.is_empty() {
+                    Some(false)
+                } else {
+                    index.scan(Some(&folders), &stop)
+                }
+            };
+            let Some(changed) = changed else {
+                return;
+            };
+            if changed {
+                unsaved = true;
+                if tx.send((generation, Arc::new(index.clone()))).is_err() {
+                    return;
+                }
+            }
+            if unsaved && saved_at.is_none_or(|at| at.elapsed() >= SAVE_INTERVAL) {
+                index.save();
+                saved_at = Some(Instant::now());
+                unsaved = false;
+            }
+
+            // Wait for watcher events to settle, a due save, or the fallback rescan
+            let waiting = Instant::now();
+            let mut first_event: Option<Instant> = None;
+            loop {
+                if stop.load(Ordering::Relaxed) {
+                    return;
+                }
+                match events.recv_timeout(Duration::from_secs(1)) {
+                    Ok(Ok(event)) => {
+                        if event.need_rescan() {
+                            full_scan = true;
+                        }
+                        if !matches!(event.kind, EventKind::Access(_)) {
+                            let parents = event.paths.iter().filter_map(|p| p.parent());
+                            dirty.extend(parents.map(Path::to_path_buf));
+                        }
+                        first_event.get_or_insert_with(Instant::now);
+                    }
+                    Ok(Err(_)) => {}
+                    Err(RecvTimeoutError::Timeout) => {
+                        if first_event.is_some() {
+                            break;
+                        }
+                    }
+                    Err(RecvTimeoutError::Disconnected) => thread::sleep(Duration::from_secs(1)),
+                }
+                if first_event.is_some_and(|at| at.elapsed() >= SETTLE_MAX)
+                    || (watcher.is_none() && waiting.elapsed() >= REFRESH_INTERVAL)
+                    || (unsaved && saved_at.is_some_and(|at| at.elapsed() >= SAVE_INTERVAL))
+                {
+                    break;
+                }
+            }
+            if watcher.is_none() && waiting.elapsed() >= REFRESH_INTERVAL {
+                full_scan = true;
+            }
+        }
+    });
+}
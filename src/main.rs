@@ -1,7 +1,19 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")] // Hide console on release
 
 mod app;
+mod cache;
+mod document;
 mod filesystem;
+mod hex;
+mod highlight;
+mod imageinfo;
+mod index;
+mod markdown;
+mod mime;
+mod pager;
+mod pdf;
+mod table;
+mod usage;
 
 use app::ExplorerApp;
 use eframe::egui;
@@ -0,0 +1,422 @@
+use eframe::egui::{self, RichText};
+use pulldown_cmark::{Event, Options, Parser, Tag, TagEnd};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+#[derive(Clone, Copy, Default)]
+struct Style {
+    strong: bool,
+    italic: bool,
+    strike: bool,
+    code: bool,
+}
+
+enum Inline {
+    Text {
+        text: String,
+        style: Style,
+        link: Option<String>,
+    },
+    Image {
+        src: String,
+        alt: String,
+    },
+    LineBreak,
+}
+
+type Cell = Vec<Inline>;
+
+enum Block {
+    Heading(usize, Vec<Inline>),
+    Paragraph { quote: usize, inlines: Vec<Inline> },
+    Item { depth: usize, marker: String, inlines: Vec<Inline> },
+    Code(String),
+    Table { header: Vec<Cell>, rows: Vec<Vec<Cell>> },
+    Rule,
+}
+
+// Rendered Markdown for the preview panel.
+//
+// The source is parsed into a flat list of blocks that egui draws directly; parsing
+// again only happens when the text changes, so editing in source mode stays cheap.
+pub struct MarkdownView {
+    cache: Option<(u64, Vec<Block>)>,
+}
+
+pub fn is_markdown(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| {
+            ["md", "markdown", "mdown", "mkd"]
+                .iter()
+                .any(|md| ext.eq_ignore_ascii_case(md))
+        })
+}
+
+impl MarkdownView {
+    pub fn new() -> Self {
+        Self { cache: None }
+    }
+
+    /// Draws `text` rendered. Links and images resolve relative to `base_dir`.
+    /// Returns the target of a clicked link to a local file.
+    pub fn show(&mut self, ui: &mut egui::Ui, text: &str, base_dir: &Path) -> Option<PathBuf> {
+        let mut hasher = DefaultHasher::new();
+        text.hash(&mut hasher);
+        let key = hasher.finish();
+        if self.cache.as_ref().is_none_or(|(cached_key, _)| *cached_key != key) {
+            self.cache = Some((key, parse(text)));
+        }
+        let blocks = &self.cache.as_ref()?.1;
+
+        let mut clicked = None;
+        for (i, block) in blocks.iter().enumerate() {
+            ui.push_id(i, |ui| show_block(ui, block, base_dir, &mut clicked));
+        }
+        clicked
+    }
+}
+
+fn parse(text: &str) -> Vec<Block> {
+    let options =
+        Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS;
+    let mut parser = BlockBuilder::default();
+    for event in Parser::new_ext(text, options) {
+        parser.event(event);
+    }
+    parser.flush();
+    parser.blocks
+}
+
+#[derive(Default)]
+struct BlockBuilder {
+    blocks: Vec<Block>,
+    inlines: Vec<Inline>,
+    style: Style,
+    link: Option<String>,
+    image: Option<(String, String)>, // Source and alt text collected so far
+    lists: Vec<Option<u64>>,         // Next number of each open list, None if bulleted
+    item_marker: Option<String>,     // Item whose first line hasn't been flushed yet
+    quote: usize,
+    code: Option<String>,
+    row: Vec<Cell>,
+    table: Option<(Vec<Cell>, Vec<Vec<Cell>>)>,
+}
+
+impl BlockBuilder {
+    // Ends the run of inline content, as an item line or a paragraph
+    fn flush(&mut self) {
+        let inlines = std::mem::take(&mut self.inlines);
+        if let Some(marker) = self.item_marker.take() {
+            let depth = self.lists.len().saturating_sub(1);
+            self.blocks.push(Block::Item { depth, marker, inlines });
+        } else if !inlines.is_empty() {
+            let quote = self.quote;
+            self.blocks.push(Block::Paragraph { quote, inlines });
+        }
+    }
+
+    fn text(&mut self, text: &str, style: Style) {
+        if let Some(code) = &mut self.code {
+            code.push_str(text);
+        } else if let Some((_, alt)) = &mut self.image {
+            alt.push_str(text);
+        } else {
+            self.inlines.push(Inline::Text {
+                text: text.to_string(),
+                style,
+                link: self.link.clone(),
+            });
+        }
+    }
+
+    fn event(&mut self, event: Event) {
+        match event {
+            Event::Start(tag) => match tag {
+                Tag::Heading { .. } | Tag::BlockQuote(_) | Tag::List(_) | Tag::Table(_) => {
+                    self.flush();
+                    match tag {
+                        Tag::BlockQuote(_) => self.quote += 1,
+                        Tag::List(start) => self.lists.push(start),
+                        Tag::Table(_) => self.table = Some((Vec::new(), Vec::new())),
+                        _ => {}
+                    }
+                }
+                Tag::Item => {
+                    self.flush();
+                    let marker = match self.lists.last_mut() {
+                        Some(Some(number)) => {
+                            *number += 1;
+                            format!("{}.", *number - 1)
+                        }
+                        _ => "•".to_string(),
+                    };
+                    self.item_marker = Some(marker);
+                }
+                Tag::CodeBlock(_) => {
+                    self.flush();
+                    self.code = Some(String::new());
+                }
+                Tag::Emphasis => self.style.italic = true,
+                Tag::Strong => self.style.strong = true,
+                Tag::Strikethrough => self.style.strike = true,
+                Tag::Link { dest_url, .. } => self.link = Some(dest_url.to_string()),
+                Tag::Image { dest_url, .. } => {
+                    self.image = Some((dest_url.to_string(), String::new()));
+                }
+                Tag::TableRow | Tag::TableHead => self.row.clear(),
+                _ => {}
+            },
+            Event::End(tag) => match tag {
+                TagEnd::Heading(level) => {
+                    let inlines = std::mem::take(&mut self.inlines);
+                    self.blocks.push(Block::Heading(level as usize, inlines));
+                }
+                TagEnd::Paragraph | TagEnd::Item => self.flush(),
+                TagEnd::BlockQuote(_) => {
+                    self.flush();
+                    self.quote = self.quote.saturating_sub(1);
+                }
+                TagEnd::List(_) => {
+                    self.flush();
+                    self.lists.pop();
+                }
+                TagEnd::CodeBlock => {
+                    if let Some(code) = self.code.take() {
+                        self.blocks.push(Block::Code(code));
+                    }
+                }
+                TagEnd::Emphasis => self.style.italic = false,
+                TagEnd::Strong => self.style.strong = false,
+                TagEnd::Strikethrough => self.style.strike = false,
+                TagEnd::Link => self.link = None,
+                TagEnd::Image => {
+                    if let Some((src, alt)) = self.image.take() {
+                        self.inlines.push(Inline::Image { src, alt });
+                    }
+                }
+                TagEnd::TableCell => {
+                    let cell = std::mem::take(&mut self.inlines);
+                    self.row.push(cell);
+                }
+                TagEnd::TableHead => {
+                    if let Some((header, _)) = &mut self.table {
+                        *header = std::mem::take(&mut self.row);
+                    }
+                }
+                TagEnd::TableRow => {
+                    if let Some((_, rows)) = &mut self.table {
+                        rows.push(std::mem::take(&mut self.row));
+                    }
+                }
+                TagEnd::Table => {
+                    if let Some((header, rows)) = self.table.take() {
+                        self.blocks.push(Block::Table { header, rows });
+                    }
+                }
+                _ => {}
+            },
+            Event::Text(text) => self.text(&text, self.style),
+            Event::Code(text) => self.text(&text, Style { code: true, ..self.style }),
+            Event::SoftBreak => self.text(" ", self.style),
+            Event::HardBreak => self.inlines.push(Inline::LineBreak),
+            Event::TaskListMarker(done) => {
+                self.text(if done { "☑ " } else { "☐ " }, self.style);
+            }
+            Event::Rule => {
+                self.flush();
+                self.blocks.push(Block::Rule);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn show_block(ui: &mut egui::Ui, block: &Block, base_dir: &Path, clicked: &mut Option<PathBuf>) {
+    match block {
+        Block::Heading(level, inlines) => {
+            let size = match level {
+                1 => 24.0,
+                2 => 20.0,
+                3 => 17.0,
+                _ => 15.0,
+            };
+            ui.add_space(6.0);
+            show_inlines(ui, inlines, Some(size), base_dir, clicked);
+            if *level <= 2 {
+                ui.separator();
+            }
+        }
+        Block::Paragraph { quote, inlines } => {
+            ui.horizontal_top(|ui| {
+                for _ in 0..*quote {
+                    ui.separator();
+                }
+                ui.vertical(|ui| show_inlines(ui, inlines, None, base_dir, clicked));
+            });
+        }
+        Block::Item { depth, marker, inlines } => {
+            ui.horizontal_top(|ui| {
+                ui.add_space(*depth as f32 * 16.0);
+                ui.label(marker);
+                ui.vertical(|ui| show_inlines(ui, inlines, None, base_dir, clicked));
+            });
+        }
+        Block::Code(code) => {
+            egui::Frame::group(ui.style())
+                .fill(ui.visuals().extreme_bg_color)
+                .show(ui, |ui| {
+                    ui.set_min_width(ui.available_width());
+                    egui::ScrollArea::horizontal().show(ui, |ui| {
+                        ui.add(
+                            egui::Label::new(RichText::new(code.trim_end_matches('\n')).monospace())
+                                .extend(),
+                        );
+                    });
+                });
+        }
+        Block::Table { header, rows } => {
+            egui::ScrollArea::horizontal().show(ui, |ui| {
+                egui::Grid::new("table").striped(true).show(ui, |ui| {
+                    for cell in header {
+                        ui.strong(plain_text(cell));
+                    }
+                    ui.end_row();
+                    for row in rows {
+                        for cell in row {
+                            show_inlines(ui, cell, None, base_dir, clicked);
+                        }
+                        ui.end_row();
+                    }
+                });
+            });
+        }
+        Block::Rule => {
+            ui.separator();
+        }
+    }
+    ui.add_space(4.0);
+}
+
+// A line of text runs, links and images, wrapped to the panel width.
+// `heading` is the font size of a heading, which is also bold.
+fn show_inlines(
+    ui: &mut egui::Ui,
+    inlines: &[Inline],
+    heading: Option<f32>,
+    base_dir: &Path,
+    clicked: &mut Option<PathBuf>,
+) {
+    ui.horizontal_wrapped(|ui| {
+        ui.spacing_mut().item_spacing.x = 0.0;
+        for inline in inlines {
+            match inline {
+                Inline::Text { text, style, link } => {
+                    let mut rich = RichText::new(text);
+                    if let Some(size) = heading {
+                        rich = rich.size(size).strong();
+                    }
+                    if style.strong {
+                        rich = rich.strong();
+                    }
+                    if style.italic {
+                        rich = rich.italics();
+                    }
+                    if style.strike {
+                        rich = rich.strikethrough();
+                    }
+                    if style.code {
+                        rich = rich.code();
+                    }
+                    match link {
+                        Some(url) if is_external(url) => {
+                            ui.hyperlink_to(rich, url).on_hover_text(url);
+                        }
+                        Some(url) => {
+                            let target = resolve(base_dir, url);
+                            if ui.link(rich).on_hover_text(url).clicked() {
+                                *clicked = target;
+                            }
+                        }
+                        None => {
+                            ui.label(rich);
+                        }
+                    }
+                }
+                Inline::Image { src, alt } => {
+                    // Remote images are never fetched: previewing a file shouldn't reach
+                    // out to whatever server it names
+                    if is_external(src) {
+                        let label = if alt.is_empty() { src } else { alt };
+                        ui.hyperlink_to(format!("🖼 {}", label), src).on_hover_text(src);
+                        continue;
+                    }
+                    match resolve(base_dir, src) {
+                        Some(path) => {
+                            let path = path.to_string_lossy().replace('\\', "/");
+                            let uri = format!("file://{}", path);
+                            let image = egui::Image::new(uri).max_width(ui.available_width());
+                            ui.add(image).on_hover_text(alt);
+                        }
+                        None => {
+                            ui.weak(alt);
+                        }
+                    }
+                }
+                Inline::LineBreak => ui.end_row(),
+            }
+        }
+    });
+}
+
+fn plain_text(inlines: &[Inline]) -> String {
+    inlines
+        .iter()
+        .filter_map(|inline| match inline {
+            Inline::Text { text, .. } => Some(text.as_str()),
+            Inline::Image { alt, .. } => Some(alt.as_str()),
+            Inline::LineBreak => None,
+        })
+        .collect()
+}
+
+fn is_external(url: &str) -> bool {
+    url.contains("://") || url.starts_with("mailto:")
+}
+
+// A relative link or image path as a file path, without any #fragment.
+// None for links within the same document.
+fn resolve(base_dir: &Path, url: &str) -> Option<PathBuf> {
+    let path = url.split('#').next().unwrap_or_default();
+    if path.is_empty() {
+        return None;
+    }
+    Some(base_dir.join(percent_decode(path)))
+}
+
+// "%20" and the like back to the bytes they stand for; malformed escapes stay as written
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = bytes
+            .get(i + 1..i + 3)
+            .filter(|_| bytes[i] == b'%')
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .filter(|hex| hex.bytes().all(|b| b.is_ascii_hexdigit()))
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
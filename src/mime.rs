@@ -0,0 +1,147 @@
+use crate::document::bomless_utf16;
+use crate::filesystem::FileCategory;
+use infer::{Infer, MatcherType};
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use std::sync::OnceLock;
+
+const SNIFF_LEN: usize = 8192;
+
+// Text editor for text files without an extension the OS would know how to open
+#[cfg(windows)]
+pub const TEXT_EDITOR: Option<&str> = Some("notepad");
+#[cfg(not(windows))]
+pub const TEXT_EDITOR: Option<&str> = None; // xdg-open/open sniff content themselves
+
+// What a file actually holds, as far as preview and opening are concerned
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ContentKind {
+    Text,
+    Image,
+    Pdf,
+    Document,
+    Archive,
+    Executable,
+    Media,
+    Binary,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Detected {
+    pub mime: &'static str,
+    pub kind: ContentKind,
+}
+
+impl Detected {
+    /// Category for icons and filters when the extension doesn't say.
+    pub fn category(&self, head: &[u8]) -> FileCategory {
+        match self.kind {
+            ContentKind::Text if head.starts_with(b"#!") => FileCategory::Script,
+            ContentKind::Text | ContentKind::Pdf | ContentKind::Document => FileCategory::Document,
+            ContentKind::Image => FileCategory::Image,
+            ContentKind::Archive => FileCategory::Archive,
+            ContentKind::Executable => FileCategory::Executable,
+            ContentKind::Media => FileCategory::Media,
+            ContentKind::Binary => FileCategory::Other,
+        }
+    }
+}
+
+// infer's built-in table plus image formats the preview can decode that it doesn't know
+fn matchers() -> &'static Infer {
+    static MATCHERS: OnceLock<Infer> = OnceLock::new();
+    MATCHERS.get_or_init(|| {
+        let mut matchers = Infer::new();
+        matchers.add("image/vnd.ms-dds", "dds", |buf| buf.starts_with(b"DDS "));
+        matchers.add("image/x-farbfeld", "ff", |buf| buf.starts_with(b"farbfeld"));
+        matchers.add("image/x-portable-anymap", "pnm", |buf| {
+            buf.len() > 2
+                && buf[0] == b'P'
+                && (b'1'..=b'6').contains(&buf[1])
+                && buf[2].is_ascii_whitespace()
+        });
+        matchers
+    })
+}
+
+fn read_head(path: &Path, len: usize) -> Option<Vec<u8>> {
+    let mut head = Vec::with_capacity(len);
+    File::open(path)
+        .ok()?
+        .take(len as u64)
+        .read_to_end(&mut head)
+        .ok()?;
+    Some(head)
+}
+
+/// Detects the content of the file at `path` from its first bytes. None if unreadable.
+pub fn detect(path: &Path) -> Option<Detected> {
+    read_head(path, SNIFF_LEN).map(|head| detect_bytes(&head, path))
+}
+
+/// Category of a file whose extension gave nothing away, from a short peek at its content.
+pub fn sniff_category(path: &Path) -> FileCategory {
+    // Magic numbers sit in the first few bytes, this only has to tell text from binary
+    match read_head(path, 512) {
+        Some(head) => detect_bytes(&head, path).category(&head),
+        None => FileCategory::Other,
+    }
+}
+
+/// Magic numbers first, then a text/binary guess. The extension only settles formats
+/// without a signature: SVG (plain text underneath) and TGA.
+pub fn detect_bytes(head: &[u8], path: &Path) -> Detected {
+    if let Some(found) = matchers().get(head) {
+        let mime = found.mime_type();
+        let kind = match found.matcher_type() {
+            _ if mime == "application/pdf" => ContentKind::Pdf,
+            MatcherType::Image => ContentKind::Image,
+            MatcherType::Custom if mime.starts_with("image/") => ContentKind::Image,
+            MatcherType::Audio | MatcherType::Video => ContentKind::Media,
+            MatcherType::Archive => ContentKind::Archive,
+            MatcherType::App => ContentKind::Executable,
+            MatcherType::Doc | MatcherType::Book => ContentKind::Document,
+            MatcherType::Text => ContentKind::Text,
+            MatcherType::Font | MatcherType::Custom => ContentKind::Binary,
+        };
+        return Detected { mime, kind };
+    }
+
+    let has_extension = |wanted: &str| {
+        path.extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case(wanted))
+    };
+    let (mime, kind) = match looks_like_text(head) {
+        true if has_extension("svg") => ("image/svg+xml", ContentKind::Image),
+        true => ("text/plain", ContentKind::Text),
+        false if has_extension("tga") => ("image/x-tga", ContentKind::Image),
+        false => ("application/octet-stream", ContentKind::Binary),
+    };
+    Detected { mime, kind }
+}
+
+// UTF-8, UTF-16 (with a BOM or by its NUL pattern), or a legacy 8-bit encoding with
+// hardly any control bytes
+fn looks_like_text(head: &[u8]) -> bool {
+    if head.starts_with(&[0xFF, 0xFE]) || head.starts_with(&[0xFE, 0xFF]) {
+        return true;
+    }
+    if bomless_utf16(head).is_some() {
+        return true;
+    }
+    if head.contains(&0) {
+        return false;
+    }
+    match std::str::from_utf8(head) {
+        Ok(_) => return true,
+        // Only cut off mid-character at the end of the sniffed block
+        Err(e) if e.error_len().is_none() => return true,
+        Err(_) => {}
+    }
+    let control = head
+        .iter()
+        .filter(|&&b| b < 0x20 && !matches!(b, b'\t' | b'\n' | b'\r' | 0x0C | 0x1B))
+        .count();
+    control * 100 <= head.len()
+}
@@ -0,0 +1,175 @@
+use memchr::memchr_iter;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+// Every this many lines the byte offset of the line start is remembered, so reaching
+// any line means scanning at most this many lines forward
+const CHECKPOINT_LINES: usize = 1024;
+const MAX_LINE_CHARS: usize = 4096;
+
+// Read-only view of a text file too big to load.
+//
+// Lines are found by an index that is built a slice at a time (`index_more`), so
+// opening a multi-GB log is instant and the viewer fills in as the scan proceeds.
+// Only the lines on screen are read from disk. The file isn't memory-mapped: another
+// program may truncate a log at any time, which here only means short reads.
+// `reload` picks up appended data for tail -f style following.
+pub struct PagedFile {
+    path: PathBuf,
+    file: File,
+    len: usize,
+    checkpoints: Vec<usize>,
+    line_count: usize, // Complete lines seen so far, plus a trailing partial one
+    indexed: usize,    // Bytes scanned for newlines
+    partial: bool,     // The last indexed line has no newline yet
+    buffer: Vec<u8>,   // Reused by `index_more`
+}
+
+impl PagedFile {
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let file = File::open(path).map_err(|e| e.to_string())?;
+        let len = file.metadata().map_err(|e| e.to_string())?.len() as usize;
+        Ok(Self {
+            path: path.to_path_buf(),
+            file,
+            len,
+            checkpoints: vec![0],
+            line_count: 0,
+            indexed: 0,
+            partial: false,
+            buffer: Vec::new(),
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.line_count
+    }
+
+    pub fn is_indexed(&self) -> bool {
+        self.indexed == self.len
+    }
+
+    /// Share of the file scanned so far, 0.0 to 1.0.
+    pub fn progress(&self) -> f32 {
+        if self.len == 0 {
+            1.0
+        } else {
+            self.indexed as f32 / self.len as f32
+        }
+    }
+
+    /// Scans up to `budget` more bytes for line starts.
+    pub fn index_more(&mut self, budget: usize) {
+        let start = self.indexed;
+        let wanted = budget.min(self.len - start);
+        self.buffer.clear();
+        let mut file = &self.file;
+        let read = file
+            .seek(SeekFrom::Start(start as u64))
+            .and_then(|_| file.take(wanted as u64).read_to_end(&mut self.buffer));
+        if read.is_err() || self.buffer.len() < wanted {
+            // Shrunk since it was opened; `reload` starts over once it notices
+            self.len = start + self.buffer.len();
+        }
+
+        // A partial last line was counted already and gets completed here
+        let mut lines = self.line_count - usize::from(self.partial);
+        for newline in memchr_iter(b'\n', &self.buffer) {
+            lines += 1;
+            if lines % CHECKPOINT_LINES == 0 {
+                self.checkpoints.push(start + newline + 1);
+            }
+        }
+        self.indexed = start + self.buffer.len();
+        if let Some(&last) = self.buffer.last() {
+            self.partial = last != b'\n';
+        }
+        self.line_count = lines + usize::from(self.partial);
+    }
+
+    /// Lines `first..first + count` (zero-based) that have been indexed, without
+    /// line endings. Overlong lines are cut short.
+    pub fn lines(&self, first: usize, count: usize) -> Vec<String> {
+        let Some(&checkpoint) = self.checkpoints.get(first / CHECKPOINT_LINES) else {
+            return Vec::new();
+        };
+        let mut file = &self.file;
+        if file.seek(SeekFrom::Start(checkpoint as u64)).is_err() {
+            return Vec::new();
+        }
+        let mut reader = BufReader::new(file.take((self.indexed - checkpoint) as u64));
+
+        for _ in 0..first % CHECKPOINT_LINES {
+            if !matches!(read_line(&mut reader, 0), Ok(Some(_))) {
+                return Vec::new();
+            }
+        }
+
+        let mut lines = Vec::with_capacity(count);
+        while lines.len() < count {
+            // Decode no more than could possibly be shown (4 bytes per char at most)
+            let Ok(Some((raw, mut cut_short))) = read_line(&mut reader, MAX_LINE_CHARS * 4) else {
+                break;
+            };
+            let raw = if cut_short { &raw[..] } else { raw.strip_suffix(b"\r").unwrap_or(&raw) };
+            let mut line = String::from_utf8_lossy(raw).into_owned();
+            if let Some((cut, _)) = line.char_indices().nth(MAX_LINE_CHARS) {
+                line.truncate(cut);
+                cut_short = true;
+            }
+            if cut_short {
+                line.push('…');
+            }
+            lines.push(line);
+        }
+        lines
+    }
+
+    /// Picks up data appended since the file was opened. Returns true if there is any.
+    /// A file that shrank (truncated or rotated) is re-read from the start.
+    pub fn reload(&mut self) -> Result<bool, String> {
+        let len = fs::metadata(&self.path).map_err(|e| e.to_string())?.len() as usize;
+        if len == self.len {
+            return Ok(false);
+        }
+        // Reopened in case the log was rotated and a new file took its name
+        self.file = File::open(&self.path).map_err(|e| e.to_string())?;
+        if len < self.len {
+            self.checkpoints = vec![0];
+            self.line_count = 0;
+            self.indexed = 0;
+            self.partial = false;
+        }
+        self.len = len;
+        Ok(true)
+    }
+}
+
+// The next line without its newline, keeping at most `keep` bytes of it, and whether
+// more was dropped. None at the end of the input.
+fn read_line(reader: &mut impl BufRead, keep: usize) -> io::Result<Option<(Vec<u8>, bool)>> {
+    let mut kept = Vec::new();
+    let mut dropped = false;
+    let mut started = false;
+    loop {
+        let buf = reader.fill_buf()?;
+        if buf.is_empty() {
+            return Ok(started.then_some((kept, dropped)));
+        }
+        started = true;
+        let newline = memchr::memchr(b'\n', buf);
+        let end = newline.unwrap_or(buf.len());
+        let room = keep.saturating_sub(kept.len());
+        kept.extend_from_slice(&buf[..end.min(room)]);
+        dropped |= end > room;
+        reader.consume(end + usize::from(newline.is_some()));
+        if newline.is_some() {
+            return Ok(Some((kept, dropped)));
+        }
+    }
+}
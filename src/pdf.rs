@@ -0,0 +1,229 @@
+use eframe::egui;
+use pdfium_render::prelude::*;
+use std::path::PathBuf;
+use std::sync::mpsc::{Receiver, Sender};
+use std::thread;
+
+// Widest page bitmap rendered, whatever the zoom
+const MAX_RENDER_WIDTH: u32 = 4096;
+
+pub struct PdfInfo {
+    pub page_count: usize,
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub subject: Option<String>,
+    pub creator: Option<String>,
+    pub producer: Option<String>,
+    pub created: Option<String>,
+}
+
+pub struct PdfRequest {
+    pub path: PathBuf,
+    pub page: usize,
+    pub width: u32,   // Bitmap width in pixels
+    pub reload: bool, // First request of a preview: read the file afresh and send its info
+}
+
+pub enum PdfEvent {
+    Info(PdfInfo),
+    Page {
+        page: usize,
+        width: u32,
+        image: egui::ColorImage,
+    },
+    PageFailed {
+        page: usize,
+        width: u32,
+        error: String,
+    },
+    Failed(String), // The document itself can't be shown
+}
+
+pub struct PdfUpdate {
+    pub path: PathBuf,
+    pub event: PdfEvent,
+}
+
+// State of the PDF preview; metadata and page bitmaps come from the renderer thread
+pub struct PdfPreview {
+    pub path: PathBuf,
+    pub info: Option<PdfInfo>,
+    pub page: usize,
+    pub texture: Option<egui::TextureHandle>, // Last rendered page, shown until the next arrives
+    pub requested: Option<(usize, u32)>,      // Page and width asked for most recently
+    pub page_error: Option<String>,           // Why the requested page didn't render
+    pub error: Option<String>,
+}
+
+impl PdfPreview {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            info: None,
+            page: 0,
+            texture: None,
+            requested: None,
+            page_error: None,
+            error: None,
+        }
+    }
+
+    /// Bitmap width to render at for a page shown `width` points wide. Rounded so that
+    /// small panel resizes don't trigger a new render.
+    pub fn render_width(width: f32, pixels_per_point: f32) -> u32 {
+        let pixels = (width * pixels_per_point / 64.0).round() as u32 * 64;
+        pixels.clamp(64, MAX_RENDER_WIDTH)
+    }
+
+    /// Applies an update from the renderer if it belongs to this document.
+    pub fn apply(&mut self, update: PdfUpdate, ctx: &egui::Context) {
+        if update.path != self.path {
+            return;
+        }
+        match update.event {
+            PdfEvent::Info(info) => self.info = Some(info),
+            // Drop renders that were already superseded
+            PdfEvent::Page { page, width, image } => {
+                if self.requested == Some((page, width)) {
+                    self.texture = Some(ctx.load_texture("pdf_page", image, Default::default()));
+                    self.page_error = None;
+                }
+            }
+            PdfEvent::PageFailed { page, width, error } => {
+                if self.requested == Some((page, width)) {
+                    self.page_error = Some(error);
+                }
+            }
+            PdfEvent::Failed(e) => self.error = Some(e),
+        }
+    }
+}
+
+// PDFium ships as a separate library: next to the executable, or installed system-wide
+fn bind() -> Option<Pdfium> {
+    let beside_exe = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(Pdfium::pdfium_platform_library_name_at_path));
+    beside_exe
+        .and_then(|library| Pdfium::bind_to_library(library).ok())
+        .or_else(|| Pdfium::bind_to_system_library().ok())
+        .map(Pdfium::new)
+}
+
+/// Renders PDF pages on a background thread. The last opened document stays loaded,
+/// so paging and zooming through it only re-renders; a new preview of it reads it again.
+pub fn spawn_renderer(rx: Receiver<PdfRequest>, tx: Sender<PdfUpdate>, ctx: egui::Context) {
+    thread::spawn(move || {
+        let pdfium = bind();
+        let mut open: Option<(PathBuf, PdfDocument)> = None;
+
+        while let Ok(mut request) = rx.recv() {
+            // Only the newest page/zoom matters
+            while let Ok(mut newer) = rx.try_recv() {
+                newer.reload |= request.reload && newer.path == request.path;
+                request = newer;
+            }
+            let send = |event| {
+                let _ = tx.send(PdfUpdate {
+                    path: request.path.clone(),
+                    event,
+                });
+                ctx.request_repaint();
+            };
+
+            let Some(pdfium) = &pdfium else {
+                send(PdfEvent::Failed(format!(
+                    "PDF rendering needs {} next to the program",
+                    Pdfium::pdfium_platform_library_name().to_string_lossy()
+                )));
+                continue;
+            };
+            if request.reload || open.as_ref().map(|(path, _)| path) != Some(&request.path) {
+                open = None;
+                // Read up front rather than letting PDFium keep the file open, which
+                // would stop it being renamed or deleted while previewed on Windows
+                let loaded = std::fs::read(&request.path)
+                    .map_err(|e| e.to_string())
+                    .and_then(|bytes| {
+                        pdfium
+                            .load_pdf_from_byte_vec(bytes, None)
+                            .map_err(|e| e.to_string())
+                    });
+                match loaded {
+                    Ok(document) => {
+                        send(PdfEvent::Info(document_info(&document)));
+                        open = Some((request.path.clone(), document));
+                    }
+                    Err(e) => {
+                        send(PdfEvent::Failed(e));
+                        continue;
+                    }
+                }
+            }
+
+            let Some((_, document)) = &open else {
+                continue;
+            };
+            send(match render_page(document, request.page, request.width) {
+                Ok(image) => PdfEvent::Page {
+                    page: request.page,
+                    width: request.width,
+                    image,
+                },
+                Err(error) => PdfEvent::PageFailed {
+                    page: request.page,
+                    width: request.width,
+                    error,
+                },
+            });
+        }
+    });
+}
+
+fn render_page(
+    document: &PdfDocument,
+    page: usize,
+    width: u32,
+) -> Result<egui::ColorImage, String> {
+    let index = PdfPageIndex::try_from(page).map_err(|_| "No such page".to_string())?;
+    let page = document.pages().get(index).map_err(|e| e.to_string())?;
+    let config = PdfRenderConfig::new().set_target_width(width as Pixels);
+    let bitmap = page.render_with_config(&config).map_err(|e| e.to_string())?;
+    let size = [bitmap.width() as usize, bitmap.height() as usize];
+    Ok(egui::ColorImage::from_rgba_unmultiplied(size, &bitmap.as_rgba_bytes()))
+}
+
+fn document_info(document: &PdfDocument) -> PdfInfo {
+    let tag = |kind| {
+        document
+            .metadata()
+            .get(kind)
+            .map(|tag| tag.value().trim().to_string())
+            .filter(|value| !value.is_empty())
+    };
+    PdfInfo {
+        page_count: document.pages().len() as usize,
+        title: tag(PdfDocumentMetadataTagType::Title),
+        author: tag(PdfDocumentMetadataTagType::Author),
+        subject: tag(PdfDocumentMetadataTagType::Subject),
+        creator: tag(PdfDocumentMetadataTagType::Creator),
+        producer: tag(PdfDocumentMetadataTagType::Producer),
+        created: tag(PdfDocumentMetadataTagType::CreationDate).map(|date| format_pdf_date(&date)),
+    }
+}
+
+// PDF dates look like "D:20240131174500+01'00'"; shown as "2024-01-31 17:45"
+fn format_pdf_date(raw: &str) -> String {
+    let digits = raw.strip_prefix("D:").unwrap_or(raw);
+    match digits.get(..12) {
+        Some(d) if d.bytes().all(|b| b.is_ascii_digit()) => format!(
+            "{}-{}-{} {}:{}",
+            &d[..4],
+            &d[4..6],
+            &d[6..8],
+            &d[8..10],
+            &d[10..12]
+        ),
+        _ => raw.to_string(),
+    }
+}
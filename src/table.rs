@@ -0,0 +1,210 @@
+use csv::{ByteRecord, Reader, ReaderBuilder};
+use std::cmp::Ordering;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+const DELIMITERS: [u8; 4] = [b',', b'\t', b';', b'|'];
+const SNIFF_LINES: usize = 20;
+
+pub fn is_table(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ["csv", "tsv", "tab"].iter().any(|t| ext.eq_ignore_ascii_case(t)))
+}
+
+// A CSV/TSV file shown as a grid.
+//
+// Records are read a batch per frame (`load_more`) so big exports open straight away
+// and fill in as they load. Rows are kept as raw byte records and decoded only when
+// drawn; sorting reorders an index instead of the records.
+pub struct CsvTable {
+    pub headers: Vec<String>,
+    pub delimiter: u8,
+    pub sort: Option<(usize, bool)>, // Column and whether ascending
+    reader: Option<Reader<File>>,    // None once everything is read
+    records: Vec<ByteRecord>,
+    order: Vec<usize>,
+    columns: usize,
+    file_len: u64,
+    bytes_read: u64,
+}
+
+impl CsvTable {
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let delimiter = detect_delimiter(path);
+        let file = File::open(path).map_err(|e| e.to_string())?;
+        let file_len = file.metadata().map_err(|e| e.to_string())?.len();
+        let mut reader = ReaderBuilder::new()
+            .delimiter(delimiter)
+            .flexible(true)
+            .from_reader(file);
+        let headers: Vec<String> = reader
+            .byte_headers()
+            .map_err(|e| e.to_string())?
+            .iter()
+            .map(|field| String::from_utf8_lossy(field).into_owned())
+            .collect();
+        Ok(Self {
+            columns: headers.len(),
+            headers,
+            delimiter,
+            sort: None,
+            reader: Some(reader),
+            records: Vec::new(),
+            order: Vec::new(),
+            file_len,
+            bytes_read: 0,
+        })
+    }
+
+    pub fn is_loaded(&self) -> bool {
+        self.reader.is_none()
+    }
+
+    /// Share of the file read so far, 0.0 to 1.0.
+    pub fn progress(&self) -> f32 {
+        if self.file_len == 0 {
+            1.0
+        } else {
+            self.bytes_read as f32 / self.file_len as f32
+        }
+    }
+
+    pub fn row_count(&self) -> usize {
+        self.order.len()
+    }
+
+    /// Widest row seen so far; ragged rows can have more fields than the header.
+    pub fn column_count(&self) -> usize {
+        self.columns
+    }
+
+    /// Reads up to `budget` more records. Malformed input ends loading with an error,
+    /// keeping the rows read before it.
+    pub fn load_more(&mut self, budget: usize) -> Result<(), String> {
+        let Some(reader) = &mut self.reader else {
+            return Ok(());
+        };
+        for _ in 0..budget {
+            let mut record = ByteRecord::new();
+            match reader.read_byte_record(&mut record) {
+                Ok(true) => {
+                    self.columns = self.columns.max(record.len());
+                    self.order.push(self.records.len());
+                    self.records.push(record);
+                }
+                Ok(false) => {
+                    self.reader = None;
+                    self.bytes_read = self.file_len;
+                    return Ok(());
+                }
+                Err(e) => {
+                    self.reader = None;
+                    return Err(e.to_string());
+                }
+            }
+        }
+        self.bytes_read = reader.position().byte();
+        Ok(())
+    }
+
+    /// Field `column` of the `row`th row in display order, empty if the row is short.
+    pub fn cell(&self, row: usize, column: usize) -> String {
+        self.order
+            .get(row)
+            .and_then(|&i| self.records[i].get(column))
+            .map(|field| String::from_utf8_lossy(field).into_owned())
+            .unwrap_or_default()
+    }
+
+    /// Sorts by `column`, flipping the direction if it is already the sort column.
+    /// Numbers sort numerically and before text.
+    pub fn sort_by(&mut self, column: usize) {
+        let ascending = !matches!(self.sort, Some((c, true)) if c == column);
+        self.sort = Some((column, ascending));
+
+        let keys: Vec<SortKey> = self
+            .records
+            .iter()
+            .map(|record| SortKey::new(record.get(column).unwrap_or_default()))
+            .collect();
+        self.order.sort_by(|&a, &b| {
+            let ordering = keys[a].cmp(&keys[b]);
+            if ascending {
+                ordering
+            } else {
+                ordering.reverse()
+            }
+        });
+    }
+}
+
+enum SortKey {
+    Number(f64),
+    Text(String),
+}
+
+impl SortKey {
+    fn new(field: &[u8]) -> Self {
+        let text = String::from_utf8_lossy(field);
+        match text.trim().parse::<f64>() {
+            Ok(n) if !n.is_nan() => SortKey::Number(n),
+            _ => SortKey::Text(text.to_lowercase()),
+        }
+    }
+
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (SortKey::Number(a), SortKey::Number(b)) => a.total_cmp(b),
+            (SortKey::Number(_), SortKey::Text(_)) => Ordering::Less,
+            (SortKey::Text(_), SortKey::Number(_)) => Ordering::Greater,
+            (SortKey::Text(a), SortKey::Text(b)) => a.cmp(b),
+        }
+    }
+}
+
+// The candidate that splits the first lines into the same number of fields, the most
+// fields winning ties. Falls back to tabs for .tsv/.tab files and commas otherwise.
+fn detect_delimiter(path: &Path) -> u8 {
+    let fallback = match path.extension().and_then(|ext| ext.to_str()) {
+        Some(ext) if ext.eq_ignore_ascii_case("csv") => b',',
+        Some(_) => b'\t',
+        None => b',',
+    };
+
+    let mut head = Vec::new();
+    let read = File::open(path).and_then(|file| file.take(64 * 1024).read_to_end(&mut head));
+    if read.is_err() {
+        return fallback;
+    }
+    let text = String::from_utf8_lossy(&head);
+    // The last line may be cut off by the read limit
+    let lines: Vec<&str> = text.lines().take(SNIFF_LINES + 1).collect();
+    let lines = if lines.len() > 1 { &lines[..lines.len() - 1] } else { &lines[..] };
+
+    DELIMITERS
+        .iter()
+        .filter_map(|&delimiter| {
+            let counts: Vec<usize> = lines
+                .iter()
+                .map(|line| count_outside_quotes(line, delimiter))
+                .collect();
+            let first = *counts.first()?;
+            (first > 0 && counts.iter().all(|&n| n == first)).then_some((delimiter, first))
+        })
+        .max_by_key(|&(delimiter, fields)| (fields, delimiter == fallback))
+        .map_or(fallback, |(delimiter, _)| delimiter)
+}
+
+fn count_outside_quotes(line: &str, delimiter: u8) -> usize {
+    let mut quoted = false;
+    line.bytes()
+        .filter(|&b| {
+            if b == b'"' {
+                quoted = !quoted;
+            }
+            b == delimiter && !quoted
+        })
+        .count()
+}
@@ -0,0 +1,179 @@
+use eframe::egui::{pos2, vec2, Rect};
+use rayon::prelude::*;
+use std::cmp::Reverse;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// One file or folder in a disk usage scan, folders carrying the total of their contents
+pub struct UsageNode {
+    pub name: String,
+    pub path: PathBuf,
+    pub size: u64,
+    pub is_dir: bool,
+    pub children: Vec<UsageNode>, // Largest first
+}
+
+impl UsageNode {
+    pub fn find(&self, path: &Path) -> Option<&UsageNode> {
+        if self.path == path {
+            return Some(self);
+        }
+        self.children
+            .iter()
+            .find(|child| path.starts_with(&child.path))
+            .and_then(|child| child.find(path))
+    }
+
+    /// Drops the node at `path` (after it was deleted) and takes its size off every
+    /// folder above it. Returns false if `path` isn't below this node.
+    pub fn remove(&mut self, path: &Path) -> bool {
+        let Some(i) = self.children.iter().position(|c| path.starts_with(&c.path)) else {
+            return false;
+        };
+        if self.children[i].path == path {
+            let removed = self.children.remove(i);
+            self.size -= removed.size;
+            return true;
+        }
+
+        let before = self.children[i].size;
+        if !self.children[i].remove(path) {
+            return false;
+        }
+        self.size -= before - self.children[i].size;
+        self.children.sort_by_key(|c| Reverse(c.size));
+        true
+    }
+
+    /// The biggest files anywhere below this node, largest first.
+    pub fn largest_files(&self, limit: usize) -> Vec<&UsageNode> {
+        let mut files = Vec::new();
+        let mut stack = vec![self];
+        while let Some(node) = stack.pop() {
+            for child in &node.children {
+                if child.is_dir {
+                    stack.push(child);
+                } else {
+                    files.push(child);
+                }
+            }
+        }
+        files.sort_by_key(|f| Reverse(f.size));
+        files.truncate(limit);
+        files
+    }
+}
+
+/// Scans everything below `path`, reading subfolders in parallel. Symlinks count as
+/// themselves and aren't followed. Returns None if cancelled.
+pub fn scan_usage(path: &Path, cancel: &AtomicBool) -> Option<UsageNode> {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string_lossy().to_string());
+    scan_node(path.to_path_buf(), name, cancel)
+}
+
+fn scan_node(path: PathBuf, name: String, cancel: &AtomicBool) -> Option<UsageNode> {
+    if cancel.load(Ordering::Relaxed) {
+        return None;
+    }
+    let metadata = fs::symlink_metadata(&path).ok();
+    let is_dir = metadata.as_ref().is_some_and(|m| m.is_dir());
+    if !is_dir {
+        return Some(UsageNode {
+            name,
+            path,
+            size: metadata.map(|m| m.len()).unwrap_or(0),
+            is_dir: false,
+            children: Vec::new(),
+        });
+    }
+
+    // Unreadable folders show up empty rather than failing the scan
+    let entries: Vec<fs::DirEntry> = fs::read_dir(&path)
+        .map(|read_dir| read_dir.flatten().collect())
+        .unwrap_or_default();
+    let mut children = entries
+        .into_par_iter()
+        .map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            scan_node(entry.path(), name, cancel)
+        })
+        .collect::<Option<Vec<_>>>()?;
+    children.sort_by_key(|c| Reverse(c.size));
+
+    Some(UsageNode {
+        name,
+        path,
+        size: children.iter().map(|c| c.size).sum(),
+        is_dir: true,
+        children,
+    })
+}
+
+/// Squarified treemap: splits `rect` into one cell per size, keeping cells close to
+/// square. `sizes` must be sorted largest first; zero sizes get an empty cell.
+pub fn treemap(sizes: &[u64], rect: Rect) -> Vec<Rect> {
+    let total: u64 = sizes.iter().sum();
+    if total == 0 {
+        return vec![Rect::NOTHING; sizes.len()];
+    }
+    let scale = rect.area() / total as f32;
+    let areas: Vec<f32> = sizes.iter().map(|&s| s as f32 * scale).collect();
+
+    let mut cells = Vec::with_capacity(sizes.len());
+    let mut rest = rect;
+    let mut start = 0;
+    while start < areas.len() {
+        if areas[start] <= 0.0 {
+            cells.push(Rect::NOTHING);
+            start += 1;
+            continue;
+        }
+
+        // Grow the row along the short side while that keeps its cells squarer
+        let side = rest.width().min(rest.height());
+        let mut end = start + 1;
+        let mut row_area = areas[start];
+        let mut worst = worst_ratio(&areas[start..end], row_area, side);
+        while end < areas.len() && areas[end] > 0.0 {
+            let ratio = worst_ratio(&areas[start..=end], row_area + areas[end], side);
+            if ratio > worst {
+                break;
+            }
+            worst = ratio;
+            row_area += areas[end];
+            end += 1;
+        }
+
+        let thickness = if side > 0.0 { row_area / side } else { 0.0 };
+        let mut offset = 0.0;
+        for &area in &areas[start..end] {
+            let length = if thickness > 0.0 { area / thickness } else { 0.0 };
+            cells.push(if rest.width() >= rest.height() {
+                Rect::from_min_size(pos2(rest.min.x, rest.min.y + offset), vec2(thickness, length))
+            } else {
+                Rect::from_min_size(pos2(rest.min.x + offset, rest.min.y), vec2(length, thickness))
+            });
+            offset += length;
+        }
+        if rest.width() >= rest.height() {
+            rest.min.x += thickness;
+        } else {
+            rest.min.y += thickness;
+        }
+        start = end;
+    }
+    cells
+}
+
+// Largest aspect ratio among a row of cells laid along a side of length `side`
+fn worst_ratio(row: &[f32], row_area: f32, side: f32) -> f32 {
+    let side2 = side * side;
+    let area2 = row_area * row_area;
+    row.iter()
+        .map(|&a| (side2 * a / area2).max(area2 / (side2 * a)))
+        .fold(0.0, f32::max)
+}